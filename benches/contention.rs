@@ -0,0 +1,195 @@
+//!Throughput of semaphore backends under contention.
+//!
+//!Run with `cargo bench`
+
+use std::time;
+
+const ITERATIONS: u32 = 100_000;
+const THREADS: [u32; 4] = [1, 2, 4, 8];
+
+trait Backend: Sync {
+    fn wait(&self);
+    fn signal(&self);
+}
+
+impl Backend for semka::Sem {
+    #[inline(always)]
+    fn wait(&self) {
+        semka::Sem::wait(self)
+    }
+
+    #[inline(always)]
+    fn signal(&self) {
+        semka::Sem::signal(self)
+    }
+}
+
+impl Backend for semka::atomic_counting::Sem {
+    #[inline(always)]
+    fn wait(&self) {
+        semka::atomic_counting::Sem::wait(self)
+    }
+
+    #[inline(always)]
+    fn signal(&self) {
+        semka::atomic_counting::Sem::signal(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Backend for semka::parking::Sem {
+    #[inline(always)]
+    fn wait(&self) {
+        semka::parking::Sem::wait(self)
+    }
+
+    #[inline(always)]
+    fn signal(&self) {
+        semka::parking::Sem::signal(self)
+    }
+}
+
+//Each thread repeatedly acquires and releases single permit
+fn contention<B: Backend>(sem: &B, threads: u32) -> time::Duration {
+    let start = time::Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    sem.wait();
+                    sem.signal();
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn report(name: &str, threads: u32, elapsed: time::Duration) {
+    let ops = u128::from(threads) * u128::from(ITERATIONS);
+    println!("{:<32} threads={} total={:?} per_op={}ns", name, threads, elapsed, elapsed.as_nanos() / ops);
+}
+
+fn bench_binary_as_mutex() {
+    let os = semka::BinarySem::new().unwrap();
+    let atomic = semka::atomic_counting::Sem::new(1);
+
+    for threads in THREADS.iter().copied() {
+        let start = time::Instant::now();
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| {
+                    for _ in 0..ITERATIONS {
+                        drop(os.lock());
+                    }
+                });
+            }
+        });
+        report("BinarySem::lock", threads, start.elapsed());
+
+        report("atomic_counting::Sem(1) as mutex", threads, contention(&atomic, threads));
+    }
+}
+
+//Same as `contention`, except permit is acquired via `wait_adaptive` with given budgets
+fn contention_adaptive(sem: &semka::Sem, threads: u32, spin_budget: u32, yield_budget: u32) -> time::Duration {
+    let start = time::Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    sem.wait_adaptive(spin_budget, yield_budget);
+                    sem.signal();
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn bench_adaptive() {
+    let sem = semka::Sem::new(1).unwrap();
+
+    for threads in THREADS.iter().copied() {
+        report("Sem::wait", threads, contention(&sem, threads));
+        for (spin_budget, yield_budget) in [(100, 0), (0, 10), (100, 10), (1_000, 100)].iter().copied() {
+            let name = format!("Sem::wait_adaptive({}, {})", spin_budget, yield_budget);
+            report(&name, threads, contention_adaptive(&sem, threads, spin_budget, yield_budget));
+        }
+    }
+}
+
+//Single thread releases `waiters` permits at once, either via `signal_many` or separate `signal` calls.
+fn bench_signal_many() {
+    let sem = semka::Sem::new(0).unwrap();
+
+    for waiters in THREADS.iter().copied() {
+        for batched in [true, false].iter().copied() {
+            let start = time::Instant::now();
+            std::thread::scope(|scope| {
+                for _ in 0..waiters {
+                    scope.spawn(|| {
+                        for _ in 0..ITERATIONS {
+                            sem.wait();
+                        }
+                    });
+                }
+
+                for _ in 0..ITERATIONS {
+                    match batched {
+                        true => sem.signal_many(waiters),
+                        false => for _ in 0..waiters {
+                            sem.signal();
+                        },
+                    }
+                }
+            });
+            let name = match batched {
+                true => "Sem::signal_many",
+                false => "Sem::signal loop",
+            };
+            report(name, waiters, start.elapsed());
+        }
+    }
+}
+
+//Spinning `atomic_counting::Sem` against `parking::Sem`, which parks waiters after short spin.
+//Threads beyond number of cores make spinning waiters compete with permit holder for CPU.
+#[cfg(feature = "std")]
+fn bench_spin_vs_park() {
+    let spin = semka::atomic_counting::Sem::new(1);
+    let park = semka::parking::Sem::new(1);
+
+    for threads in THREADS.iter().copied().chain(core::iter::once(32)) {
+        report("atomic_counting::Sem (spin)", threads, contention(&spin, threads));
+        report("parking::Sem (park)", threads, contention(&park, threads));
+    }
+}
+
+fn main() {
+    for permits in [1, 4].iter().copied() {
+        let os = semka::Sem::new(permits).unwrap();
+        let atomic = semka::atomic_counting::Sem::new(permits);
+        println!("permits={}", permits);
+
+        for threads in THREADS.iter().copied() {
+            report("Sem", threads, contention(&os, threads));
+            report("atomic_counting::Sem", threads, contention(&atomic, threads));
+        }
+    }
+
+    println!("adaptive");
+    bench_adaptive();
+
+    println!("signal_many");
+    bench_signal_many();
+
+    println!("binary");
+    bench_binary_as_mutex();
+
+    #[cfg(feature = "std")]
+    {
+        println!("spin vs park");
+        bench_spin_vs_park();
+    }
+}
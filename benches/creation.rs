@@ -0,0 +1,29 @@
+//!Overhead of creating semaphores.
+//!
+//!Run with `cargo bench --features futex` (or `wait_on_address` on Windows)
+
+use std::time;
+
+const ITERATIONS: u32 = 100_000;
+
+fn report(name: &str, elapsed: time::Duration) {
+    println!("{:<16} total={:?} per_op={}ns", name, elapsed, elapsed.as_nanos() / u128::from(ITERATIONS));
+}
+
+fn main() {
+    let start = time::Instant::now();
+    for _ in 0..ITERATIONS {
+        let sem = semka::Sem::new(1).unwrap();
+        sem.signal();
+        std::hint::black_box(sem);
+    }
+    report("Sem", start.elapsed());
+
+    let start = time::Instant::now();
+    for _ in 0..ITERATIONS {
+        let sem = semka::futex::Sem::new(1);
+        sem.signal();
+        std::hint::black_box(sem);
+    }
+    report("futex::Sem", start.elapsed());
+}
@@ -0,0 +1,43 @@
+//!Reporting of failed misuse checks, enabled by `lean_assert` feature.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+///Hook called with static description of failed check.
+pub type Hook = fn(&'static str);
+
+//Stores `Hook` as integer, zero if not set
+static HOOK: AtomicUsize = AtomicUsize::new(0);
+
+///Installs `hook` to be called when misuse check fails, replacing previous one.
+///
+///If `hook` returns, failure results in panic with static message.
+///To avoid unwinding (e.g. in signal handler or without panic handler capable of formatting), `hook` should abort.
+pub fn set_hook(hook: Hook) {
+    HOOK.store(hook as usize, Ordering::Release);
+}
+
+///Removes installed hook, if any.
+pub fn take_hook() -> Option<Hook> {
+    match HOOK.swap(0, Ordering::AcqRel) {
+        0 => None,
+        hook => Some(unsafe {
+            core::mem::transmute::<usize, Hook>(hook)
+        }),
+    }
+}
+
+#[cold]
+#[inline(never)]
+pub(crate) fn failed(msg: &'static str) -> ! {
+    match HOOK.load(Ordering::Acquire) {
+        0 => (),
+        hook => {
+            let hook = unsafe {
+                core::mem::transmute::<usize, Hook>(hook)
+            };
+            hook(msg);
+        }
+    }
+
+    panic!("Semaphore misuse check failed");
+}
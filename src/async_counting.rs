@@ -0,0 +1,265 @@
+//!Asynchronous counting semaphore.
+//!
+//!Counter is plain `AtomicU32`, as in [atomic_counting::Sem](../atomic_counting/struct.Sem.html),
+//!while waiting tasks register their `Waker` in FIFO list of listeners, in the manner of `event-listener` crate.
+//!`signal` wakes listener at the head of list, which then attempts to decrement counter again.
+//!
+//!It requires neither OS semaphore nor runtime, and works with any executor.
+//!OS backed [Sem](../struct.Sem.html) has no asynchronous wait, as it would have to block executor's thread.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{self, AtomicU32, Ordering};
+use core::task::{Context, Poll, Waker};
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+
+struct Listeners {
+    next_key: u64,
+    queue: VecDeque<(u64, Waker)>,
+}
+
+impl Listeners {
+    fn position(&self, key: u64) -> Option<usize> {
+        self.queue.iter().position(|(listener, _)| *listener == key)
+    }
+
+    fn notify(&mut self, count: u32) {
+        for _ in 0..count {
+            match self.queue.pop_front() {
+                Some((_, waker)) => waker.wake(),
+                None => break,
+            }
+        }
+    }
+}
+
+///Counting semaphore based on atomic counter, with asynchronous wait.
+pub struct Sem {
+    count: AtomicU32,
+    //Number of registered listeners, used to skip locking in `signal`
+    listeners_len: AtomicU32,
+    listeners: Mutex<Listeners>,
+}
+
+impl Sem {
+    ///Maximum value of semaphore.
+    pub const MAX: u32 = u32::max_value();
+
+    ///Creates new instance with `init` as initial value.
+    pub const fn new(init: u32) -> Self {
+        Self {
+            count: AtomicU32::new(init),
+            listeners_len: AtomicU32::new(0),
+            listeners: Mutex::new(Listeners {
+                next_key: 0,
+                queue: VecDeque::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Listeners> {
+        //Listeners are never left in inconsistent state, so poison can be ignored
+        match self.listeners.lock() {
+            Ok(listeners) => listeners,
+            Err(error) => error.into_inner(),
+        }
+    }
+
+    #[inline]
+    ///Returns current value.
+    pub fn value(&self) -> u32 {
+        self.count.load(Ordering::Acquire)
+    }
+
+    #[inline(always)]
+    ///Returns future, decrementing self once it is signaled.
+    ///
+    ///Dropping future before completion cancels wait, passing received notification, if any, to the next listener.
+    pub fn wait_async(&self) -> Acquire<'_> {
+        Acquire {
+            sem: self,
+            key: None,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns future, decrementing self once it is signaled, which resolves into guard that increments self on drop.
+    ///
+    ///Permit is released whenever guard is dropped, including cancellation of task holding it,
+    ///and guard may be dropped on any thread.
+    pub fn acquire_async(&self) -> AcquirePermit<'_> {
+        AcquirePermit {
+            acquire: self.wait_async(),
+        }
+    }
+
+    #[inline]
+    ///Attempts to decrement self, returning guard that increments self on drop.
+    ///
+    ///Returns `None` if self is not signaled.
+    pub fn try_acquire(&self) -> Option<AsyncSemGuard<'_>> {
+        match self.try_wait() {
+            true => Some(AsyncSemGuard {
+                sem: self,
+            }),
+            false => None,
+        }
+    }
+
+    ///Attempts to decrement self, returning whether self was signaled or not.
+    ///
+    ///Returns `true` if self was signaled.
+    ///
+    ///Returns `false` otherwise.
+    pub fn try_wait(&self) -> bool {
+        let mut count = self.count.load(Ordering::Relaxed);
+        while count > 0 {
+            match self.count.compare_exchange_weak(count, count - 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => count = actual,
+            }
+        }
+
+        false
+    }
+
+    fn notify(&self, count: u32) {
+        if self.listeners_len.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+
+        let mut listeners = self.lock();
+        listeners.notify(count);
+        self.listeners_len.store(listeners.queue.len() as u32, Ordering::SeqCst);
+    }
+
+    #[inline]
+    ///Increments self, waking waiting task, if any.
+    pub fn signal(&self) {
+        let count = self.count.fetch_add(1, Ordering::SeqCst);
+        strict_assert_ne!(count, Self::MAX, "Semaphore value overflow");
+
+        self.notify(1);
+    }
+
+    #[inline]
+    ///Increments self `count` times, waking up to `count` waiting tasks.
+    pub fn signal_many(&self, count: u32) {
+        let prev = self.count.fetch_add(count, Ordering::SeqCst);
+        strict_assert!(prev.checked_add(count).is_some(), "Semaphore value overflow");
+
+        self.notify(count);
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+///Future returned by [Sem::wait_async](struct.Sem.html#method.wait_async).
+pub struct Acquire<'a> {
+    sem: &'a Sem,
+    //Key of registered listener, which is removed from list once notified
+    key: Option<u64>,
+}
+
+impl Future for Acquire<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let sem = self.sem;
+        if sem.try_wait() {
+            if let Some(key) = self.key.take() {
+                let mut listeners = sem.lock();
+                if let Some(idx) = listeners.position(key) {
+                    listeners.queue.remove(idx);
+                    sem.listeners_len.store(listeners.queue.len() as u32, Ordering::SeqCst);
+                }
+            }
+            return Poll::Ready(());
+        }
+
+        let mut listeners = sem.lock();
+        let position = self.key.and_then(|key| listeners.position(key));
+        match position {
+            Some(idx) => {
+                if !listeners.queue[idx].1.will_wake(cx.waker()) {
+                    listeners.queue[idx].1 = cx.waker().clone();
+                }
+            },
+            //Either first poll or notified, but permit was taken by someone else.
+            None => {
+                let key = listeners.next_key;
+                listeners.next_key = key.wrapping_add(1);
+                listeners.queue.push_back((key, cx.waker().clone()));
+                self.key = Some(key);
+            },
+        }
+        //Either `signal` observes us as listener or we observe incremented count
+        sem.listeners_len.store(listeners.queue.len() as u32, Ordering::SeqCst);
+        atomic::fence(Ordering::SeqCst);
+
+        if sem.try_wait() {
+            if let Some(idx) = self.key.take().and_then(|key| listeners.position(key)) {
+                listeners.queue.remove(idx);
+                sem.listeners_len.store(listeners.queue.len() as u32, Ordering::SeqCst);
+            }
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            let mut listeners = self.sem.lock();
+            match listeners.position(key) {
+                Some(idx) => {
+                    listeners.queue.remove(idx);
+                },
+                //Notification was consumed by us, so pass it on if permit is still available
+                None => if self.sem.value() > 0 {
+                    listeners.notify(1);
+                },
+            }
+            self.sem.listeners_len.store(listeners.queue.len() as u32, Ordering::SeqCst);
+        }
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+///Future returned by [Sem::acquire_async](struct.Sem.html#method.acquire_async).
+pub struct AcquirePermit<'a> {
+    acquire: Acquire<'a>,
+}
+
+impl<'a> Future for AcquirePermit<'a> {
+    type Output = AsyncSemGuard<'a>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let sem = self.acquire.sem;
+        Pin::new(&mut self.acquire).poll(cx).map(|()| AsyncSemGuard {
+            sem
+        })
+    }
+}
+
+///Semaphore permit, which signals semaphore on drop.
+pub struct AsyncSemGuard<'a> {
+    sem: &'a Sem,
+}
+
+impl<'a> AsyncSemGuard<'a> {
+    #[inline(always)]
+    ///Returns semaphore this permit belongs to.
+    pub fn sem(&self) -> &'a Sem {
+        self.sem
+    }
+}
+
+impl Drop for AsyncSemGuard<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.sem.signal();
+    }
+}
@@ -0,0 +1,212 @@
+//!Pure Rust counting semaphore.
+//!
+//!Does not rely on OS primitives, which makes it portable, but waiting is performed via busy-wait,
+//!consuming CPU for as long as thread waits, unlike OS backed [Sem](../struct.Sem.html), which puts thread to sleep.
+//!Suitable only when waits are expected to be short.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::spin::SpinStrategy;
+
+#[cfg(feature = "std")]
+use core::time;
+
+//Number of failed attempts after which waiter starts yielding CPU.
+#[cfg(feature = "std")]
+const SPIN_LIMIT: u32 = 100;
+
+#[inline(always)]
+fn backoff(_attempt: u32) {
+    #[cfg(feature = "std")]
+    if _attempt >= SPIN_LIMIT {
+        std::thread::yield_now();
+        return;
+    }
+
+    core::hint::spin_loop();
+}
+
+///Counting semaphore based on atomic counter.
+pub struct Sem {
+    count: AtomicU32,
+}
+
+impl Sem {
+    ///Maximum value of semaphore.
+    pub const MAX: u32 = u32::max_value();
+
+    ///Whether `wait_timeout` is supported.
+    ///
+    ///It requires clock, hence it is available only with `std` feature.
+    pub const SUPPORTS_TIMEOUT: bool = cfg!(feature = "std");
+
+    ///Granularity of timeout accepted by `wait_timeout`.
+    ///
+    ///Elapsed time is measured via `Instant`, which is nanosecond on supported platforms.
+    pub const TIMEOUT_RESOLUTION: core::time::Duration = core::time::Duration::from_nanos(1);
+
+    ///Creates new instance with `init` as initial value.
+    pub const fn new(init: u32) -> Self {
+        Self {
+            count: AtomicU32::new(init),
+        }
+    }
+
+    #[inline]
+    ///Returns current value.
+    pub fn value(&self) -> u32 {
+        self.count.load(Ordering::Acquire)
+    }
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise spins until signal.
+    ///
+    ///With `std` feature, after short spin, waiter yields CPU between attempts, so it doesn't occupy core
+    ///while semaphore is held for long time. It is still busy-wait, as thread is never put to sleep.
+    pub fn wait(&self) {
+        let mut attempt = 0u32;
+        while !self.try_wait() {
+            backoff(attempt);
+            attempt = attempt.saturating_add(1);
+        }
+    }
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise busy-waits until signal, pausing between attempts according to `strategy`.
+    ///
+    ///`wait` behaves as if it used [PureSpin](../spin/struct.PureSpin.html) without `std` feature,
+    ///and switches to [Yield](../spin/struct.Yield.html) after short spin with it.
+    pub fn wait_with_strategy<S: SpinStrategy>(&self, strategy: &S) {
+        let mut attempt = 0u32;
+        while !self.try_wait() {
+            strategy.spin(attempt);
+            attempt = attempt.saturating_add(1);
+        }
+    }
+
+    ///Attempts to decrement self, returning whether self was signaled or not.
+    ///
+    ///Returns `true` if self was signaled.
+    ///
+    ///Returns `false` otherwise.
+    pub fn try_wait(&self) -> bool {
+        let mut count = self.count.load(Ordering::Relaxed);
+        while count > 0 {
+            match self.count.compare_exchange_weak(count, count - 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => count = actual,
+            }
+        }
+
+        false
+    }
+
+    ///Attempts to decrement self by `count` at once, returning whether it succeeded.
+    ///
+    ///Decrement is performed by single atomic operation, only if current value is at least `count`,
+    ///so permits are never partially acquired.
+    ///Such atomicity is available only for atomic based semaphores, not for OS backed [Sem](../struct.Sem.html).
+    ///
+    ///Returns `true` if `count` permits were acquired.
+    ///
+    ///Returns `false` otherwise, leaving value unchanged.
+    pub fn try_wait_many(&self, count: u32) -> bool {
+        let mut value = self.count.load(Ordering::Relaxed);
+        while value >= count {
+            match self.count.compare_exchange_weak(value, value - count, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => value = actual,
+            }
+        }
+
+        false
+    }
+
+    ///Attempts to decrement self, spinning up to `spins` times between attempts, returning whether self was signaled or not.
+    ///
+    ///Makes `spins + 1` attempts, hence with zero `spins` it is equivalent to `try_wait`.
+    ///
+    ///Bounds busy-wait by number of iterations rather than time, hence it doesn't require clock.
+    ///Time taken by single iteration depends on platform and contention,
+    ///so relationship between `spins` and wall time is only approximate.
+    ///
+    ///Returns `true` if self was signaled within `spins + 1` attempts
+    ///
+    ///Returns `false` otherwise
+    pub fn wait_timeout_with(&self, spins: u32) -> bool {
+        for _ in 0..spins {
+            if self.try_wait() {
+                return true;
+            }
+
+            core::hint::spin_loop();
+        }
+
+        self.try_wait()
+    }
+
+    #[cfg(feature = "std")]
+    ///Attempts to decrement self within provided time, returning whether self was signaled or not.
+    ///
+    ///Spins until signal or until `timeout` elapses, yielding CPU in the same way as `wait`.
+    ///
+    ///Returns `true` if self was signaled within specified timeout
+    ///
+    ///Returns `false` otherwise
+    pub fn wait_timeout(&self, timeout: time::Duration) -> bool {
+        let start = std::time::Instant::now();
+        let mut attempt = 0u32;
+        loop {
+            if self.try_wait() {
+                break true;
+            } else if start.elapsed() >= timeout {
+                break false;
+            }
+
+            backoff(attempt);
+            attempt = attempt.saturating_add(1);
+        }
+    }
+
+    #[inline]
+    ///Increments self, allowing waiting thread to proceed.
+    pub fn signal(&self) {
+        let count = self.count.fetch_add(1, Ordering::Release);
+        strict_assert_ne!(count, Self::MAX, "Semaphore value overflow");
+    }
+
+    #[inline]
+    ///Increments self `count` times, allowing waiting threads to proceed.
+    pub fn signal_many(&self, count: u32) {
+        let prev = self.count.fetch_add(count, Ordering::Release);
+        strict_assert!(prev.checked_add(count).is_some(), "Semaphore value overflow");
+    }
+}
+
+#[cfg(feature = "std")]
+impl crate::Semaphore for Sem {
+    const SUPPORTS_TIMEOUT: bool = Sem::SUPPORTS_TIMEOUT;
+    const TIMEOUT_RESOLUTION: time::Duration = Sem::TIMEOUT_RESOLUTION;
+
+    #[inline(always)]
+    fn wait(&self) {
+        Sem::wait(self)
+    }
+
+    #[inline(always)]
+    fn try_wait(&self) -> bool {
+        Sem::try_wait(self)
+    }
+
+    #[inline(always)]
+    fn wait_timeout(&self, timeout: time::Duration) -> bool {
+        Sem::wait_timeout(self, timeout)
+    }
+
+    #[inline(always)]
+    fn signal(&self) {
+        Sem::signal(self)
+    }
+}
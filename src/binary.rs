@@ -0,0 +1,129 @@
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Sem;
+
+///Binary semaphore, allowing only single holder at a time.
+///
+///Backed by OS semaphore with single permit, hence blocks instead of spinning under contention.
+///
+///With `std` feature, similarly to `std::sync::Mutex`, semaphore becomes poisoned once thread panics while holding lock.
+///Unlike `Mutex`, lock is still acquired normally, while poison is reported via `BinaryLock::is_poisoned`.
+pub struct BinarySem {
+    sem: Sem,
+    #[cfg(feature = "std")]
+    poisoned: AtomicBool,
+}
+
+impl BinarySem {
+    ///Creates new uninit instance.
+    ///
+    ///It is UB to use it until `init` is called.
+    pub const unsafe fn new_uninit() -> Self {
+        Self {
+            sem: Sem::new_uninit(),
+            #[cfg(feature = "std")]
+            poisoned: AtomicBool::new(false),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns whether semaphore is successfully initialized
+    pub fn is_init(&self) -> bool {
+        self.sem.is_init()
+    }
+
+    #[must_use]
+    #[inline]
+    ///Initializes semaphore in unlocked state.
+    ///
+    ///Returns `true` on success.
+    ///
+    ///Returns `false` if semaphore is already initialized or initialization failed.
+    pub fn init(&self) -> bool {
+        self.sem.init(1)
+    }
+
+    ///Creates new instance in unlocked state.
+    pub fn new() -> Option<Self> {
+        Sem::new(1).map(|sem| Self {
+            sem,
+            #[cfg(feature = "std")]
+            poisoned: AtomicBool::new(false),
+        })
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    ///Returns `true` if thread panicked while holding lock.
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    ///Clears poisoned state, once protected data is known to be restored.
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Relaxed);
+    }
+
+    ///Acquires lock, awaiting until it is released by current holder.
+    pub fn lock(&self) -> BinaryLock<'_> {
+        self.sem.wait();
+        BinaryLock::new(self)
+    }
+
+    #[inline]
+    ///Attempts to acquire lock, returning `None` if it is already held.
+    pub fn try_lock(&self) -> Option<BinaryLock<'_>> {
+        match self.sem.try_wait() {
+            true => Some(BinaryLock::new(self)),
+            false => None,
+        }
+    }
+
+    ///Attempts to acquire lock within provided time, returning `None` if it is not released in time.
+    pub fn try_lock_for(&self, timeout: core::time::Duration) -> Option<BinaryLock<'_>> {
+        match self.sem.wait_timeout(timeout) {
+            true => Some(BinaryLock::new(self)),
+            false => None,
+        }
+    }
+}
+
+///Lock of binary semaphore, which releases it on drop.
+///
+///Release happens exactly once, when lock is dropped, after which semaphore can be locked again.
+pub struct BinaryLock<'a> {
+    sem: &'a BinarySem,
+}
+
+impl<'a> BinaryLock<'a> {
+    #[inline(always)]
+    fn new(sem: &'a BinarySem) -> Self {
+        Self {
+            sem
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    ///Returns `true` if previous holder of lock panicked while holding it.
+    ///
+    ///State is only changed by holder of lock, so it remains the same until this lock is dropped.
+    pub fn is_poisoned(&self) -> bool {
+        self.sem.is_poisoned()
+    }
+}
+
+impl Drop for BinaryLock<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        //Flag is published to next holder by release of semaphore
+        #[cfg(feature = "std")]
+        if std::thread::panicking() {
+            self.sem.poisoned.store(true, Ordering::Relaxed);
+        }
+        self.sem.sem.signal();
+    }
+}
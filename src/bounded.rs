@@ -0,0 +1,144 @@
+use core::mem;
+
+use crate::Sem;
+
+///Coordination of `N` slots between producers and consumers.
+///
+///Textbook pair of counting semaphores: one counts empty slots, initially `capacity`, and other counts filled slots, initially zero.
+///Producer acquires empty slot, fills it and releases it as filled, while consumer does the reverse.
+///Storage of slots (e.g. ring buffer) is responsibility of user.
+pub struct BoundedCounter {
+    empty: Sem,
+    filled: Sem,
+    capacity: u32,
+}
+
+impl BoundedCounter {
+    ///Creates new instance with `capacity` empty slots.
+    pub fn new(capacity: u32) -> Option<Self> {
+        Some(Self {
+            empty: Sem::new(capacity)?,
+            filled: Sem::new(0)?,
+            capacity,
+        })
+    }
+
+    #[inline(always)]
+    ///Returns total number of slots.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    #[inline(always)]
+    ///Acquires empty slot, awaiting until it is available.
+    pub fn acquire_empty(&self) {
+        self.empty.wait();
+    }
+
+    #[inline(always)]
+    ///Attempts to acquire empty slot, returning `true` on success.
+    pub fn try_acquire_empty(&self) -> bool {
+        self.empty.try_wait()
+    }
+
+    #[inline(always)]
+    ///Releases previously acquired empty slot as filled, waking consumer.
+    pub fn release_filled(&self) {
+        self.filled.signal();
+    }
+
+    #[inline(always)]
+    ///Acquires filled slot, awaiting until it is available.
+    pub fn acquire_filled(&self) {
+        self.filled.wait();
+    }
+
+    #[inline(always)]
+    ///Attempts to acquire filled slot, returning `true` on success.
+    pub fn try_acquire_filled(&self) -> bool {
+        self.filled.try_wait()
+    }
+
+    #[inline(always)]
+    ///Releases previously acquired filled slot as empty, waking producer.
+    pub fn release_empty(&self) {
+        self.empty.signal();
+    }
+
+    ///Acquires empty slot, awaiting until it is available.
+    ///
+    ///Slot is released as filled once [commit](struct.EmptySlot.html#method.commit) is called, otherwise it is returned as empty on drop.
+    pub fn produce(&self) -> EmptySlot<'_> {
+        self.acquire_empty();
+        EmptySlot {
+            counter: self,
+        }
+    }
+
+    ///Acquires empty slot if it is available, otherwise returns `None`.
+    pub fn try_produce(&self) -> Option<EmptySlot<'_>> {
+        match self.try_acquire_empty() {
+            true => Some(EmptySlot {
+                counter: self,
+            }),
+            false => None,
+        }
+    }
+
+    ///Acquires filled slot, awaiting until it is available.
+    ///
+    ///Slot is released as empty on drop.
+    pub fn consume(&self) -> FilledSlot<'_> {
+        self.acquire_filled();
+        FilledSlot {
+            counter: self,
+        }
+    }
+
+    ///Acquires filled slot if it is available, otherwise returns `None`.
+    pub fn try_consume(&self) -> Option<FilledSlot<'_>> {
+        match self.try_acquire_filled() {
+            true => Some(FilledSlot {
+                counter: self,
+            }),
+            false => None,
+        }
+    }
+}
+
+#[must_use]
+///Empty slot acquired from [BoundedCounter](struct.BoundedCounter.html).
+///
+///Returned as empty on drop, unless committed.
+pub struct EmptySlot<'a> {
+    counter: &'a BoundedCounter,
+}
+
+impl EmptySlot<'_> {
+    #[inline]
+    ///Releases slot as filled.
+    pub fn commit(self) {
+        self.counter.release_filled();
+        mem::forget(self);
+    }
+}
+
+impl Drop for EmptySlot<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.counter.release_empty();
+    }
+}
+
+#[must_use]
+///Filled slot acquired from [BoundedCounter](struct.BoundedCounter.html), which is released as empty on drop.
+pub struct FilledSlot<'a> {
+    counter: &'a BoundedCounter,
+}
+
+impl Drop for FilledSlot<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.counter.release_empty();
+    }
+}
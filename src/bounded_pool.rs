@@ -0,0 +1,69 @@
+use std::sync::Arc;
+use std::thread;
+
+use crate::Sem;
+
+//Permit owned by worker thread, released once task completes or panics.
+struct Permit(Arc<Sem>);
+
+impl Drop for Permit {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.signal();
+    }
+}
+
+///Spawns threads, limiting number of concurrently running tasks.
+///
+///Each task runs on its own thread, while holding permit of internal counting semaphore,
+///which is released once task returns or panics.
+pub struct BoundedPool {
+    sem: Arc<Sem>,
+    capacity: u32,
+}
+
+impl BoundedPool {
+    ///Creates new instance, allowing at most `capacity` concurrent tasks.
+    pub fn new(capacity: u32) -> Option<Self> {
+        Sem::new(capacity).map(|sem| Self {
+            sem: Arc::new(sem),
+            capacity,
+        })
+    }
+
+    #[inline(always)]
+    ///Returns maximum number of concurrent tasks.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    fn spawn_with_permit<F: FnOnce() + Send + 'static>(&self, task: F) -> thread::JoinHandle<()> {
+        let permit = Permit(self.sem.clone());
+        thread::spawn(move || {
+            let _permit = permit;
+            task()
+        })
+    }
+
+    ///Spawns `task`, awaiting until there is free slot.
+    ///
+    ///## Panics
+    ///
+    ///If OS fails to create thread, same as `std::thread::spawn`.
+    pub fn spawn<F: FnOnce() + Send + 'static>(&self, task: F) -> thread::JoinHandle<()> {
+        self.sem.wait();
+        self.spawn_with_permit(task)
+    }
+
+    ///Spawns `task` if there is free slot, otherwise returns `None`.
+    ///
+    ///## Panics
+    ///
+    ///If OS fails to create thread, same as `std::thread::spawn`.
+    pub fn try_spawn<F: FnOnce() + Send + 'static>(&self, task: F) -> Option<thread::JoinHandle<()>> {
+        match self.sem.try_wait() {
+            true => Some(self.spawn_with_permit(task)),
+            false => None,
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::Sem;
+
+///Builder of [Sem](struct.Sem.html), accumulating options of semaphore creation.
+///
+///Options that are not meaningful on current platform are ignored:
+///
+///- `max` - Limits value of semaphore. It is enforced by Windows, while elsewhere it is emulated (see `Sem::new_with_max`);
+///- `fair` - Windows only, see `Sem::new_fair`;
+///- `inheritable` - Windows only, see `Sem::new_inheritable`;
+///- `policy` - Mac only, see `Sem::new_with_policy`.
+///
+///## Usage
+///
+///```rust
+///let sem = semka::SemBuilder::new().init(2).build().unwrap();
+///assert!(sem.try_wait());
+///
+///let sem = semka::SemBuilder::new().init(1).max(1).fair(true).build().unwrap();
+///assert!(sem.try_wait());
+///```
+#[derive(Debug, Clone, Copy)]
+pub struct SemBuilder {
+    init: u32,
+    max: u32,
+    fair: bool,
+    inheritable: bool,
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+    policy: crate::SyncPolicy,
+}
+
+impl SemBuilder {
+    ///Creates new builder with initial value of 0.
+    pub const fn new() -> Self {
+        Self {
+            init: 0,
+            max: Sem::MAX,
+            fair: false,
+            inheritable: false,
+            #[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+            policy: crate::SyncPolicy::Fifo,
+        }
+    }
+
+    #[inline(always)]
+    ///Sets initial value.
+    pub const fn init(mut self, init: u32) -> Self {
+        self.init = init;
+        self
+    }
+
+    #[inline(always)]
+    ///Sets maximum value.
+    ///
+    ///Building fails if it is zero, less than initial value or greater than `Sem::MAX`.
+    pub const fn max(mut self, max: u32) -> Self {
+        self.max = max;
+        self
+    }
+
+    #[inline(always)]
+    ///Sets whether semaphore should be fair.
+    ///
+    ///Only meaningful on Windows.
+    pub const fn fair(mut self, fair: bool) -> Self {
+        self.fair = fair;
+        self
+    }
+
+    #[inline(always)]
+    ///Sets whether semaphore handle should be inheritable by child processes.
+    ///
+    ///Only meaningful on Windows.
+    pub const fn inheritable(mut self, inheritable: bool) -> Self {
+        self.inheritable = inheritable;
+        self
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+    #[inline(always)]
+    ///Sets mach semaphore policy.
+    pub const fn policy(mut self, policy: crate::SyncPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    #[cfg(windows)]
+    ///Creates semaphore, returning `None` if creation fails.
+    pub fn build(self) -> Option<Sem> {
+        Sem::with_options(self.init, self.max, self.fair, self.inheritable)
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+    ///Creates semaphore, returning `None` if creation fails.
+    pub fn build(self) -> Option<Sem> {
+        Sem::with_options(self.init, self.max, self.policy)
+    }
+
+    #[cfg(any(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))), target_os = "fuchsia"))]
+    ///Creates semaphore, returning `None` if creation fails.
+    pub fn build(self) -> Option<Sem> {
+        Sem::new_with_max(self.init, self.max)
+    }
+}
+
+impl Default for SemBuilder {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sem {
+    ///Creates new instance with value of `init + extra`.
+    ///
+    ///Semaphore is created with combined value at once, instead of signaling `extra` times after creation,
+    ///so no intermediate value is ever observable.
+    ///
+    ///Returns `None` if `init + extra` overflows or exceeds `Sem::MAX`, or creation fails.
+    pub fn new_signalled(init: u32, extra: u32) -> Option<Self> {
+        Sem::new(init.checked_add(extra)?)
+    }
+
+    ///Creates `N` instances, initializing each with `init`.
+    ///
+    ///If any of them fails to initialize, already initialized ones are closed and `None` is returned.
+    pub fn new_array<const N: usize>(init: u32) -> Option<[Self; N]> {
+        //Closing uninitialized semaphore does nothing, so array can be dropped at any point
+        let result: [Self; N] = core::array::from_fn(|_| unsafe {
+            Self::new_uninit()
+        });
+
+        for sem in result.iter() {
+            if !sem.init(init) {
+                return crate::unlikely(None);
+            }
+        }
+
+        Some(result)
+    }
+}
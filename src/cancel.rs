@@ -0,0 +1,30 @@
+use core::time;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Sem;
+
+//Interval between checks of cancellation flag
+const CANCEL_CHECK_INTERVAL: time::Duration = time::Duration::from_millis(5);
+
+impl Sem {
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal, until `cancel` is set to `true`.
+    ///
+    ///There is no way to wake up waiting thread on change of `cancel`, hence wait is performed in slices of 5ms,
+    ///checking `cancel` in between. As result cancellation is observed with latency of up to 5ms,
+    ///while blocked thread wakes up every 5ms.
+    ///
+    ///Returns `true` if self was signaled.
+    ///
+    ///Returns `false` if wait was cancelled.
+    pub fn wait_cancellable(&self, cancel: &AtomicBool) -> bool {
+        while !cancel.load(Ordering::Acquire) {
+            if self.wait_timeout(CANCEL_CHECK_INTERVAL) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
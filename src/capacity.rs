@@ -0,0 +1,60 @@
+use crate::Sem;
+
+impl Sem {
+    #[inline(always)]
+    ///Increases capacity by `count` permits.
+    ///
+    ///Equivalent to `signal_many`, hence capacity cannot exceed semaphore's maximum.
+    pub fn grow(&self, count: u32) {
+        self.signal_many(count);
+    }
+
+    ///Increments self up to `count` times, stopping once semaphore's maximum is reached.
+    ///
+    ///Unlike `signal_many`, it is not misuse to exceed maximum, hence it never panics.
+    ///Semaphore created without explicit maximum is only limited by `Sem::MAX`, so normally all `count` permits are added.
+    ///On mac, maximum is only tracked for semaphores created via `new_with_max`, as mach semaphore neither reports
+    ///its value nor overflow, hence otherwise it is up to user to not exceed `Sem::MAX`.
+    ///
+    ///Returns number of permits actually added, which is less than `count` if semaphore became saturated
+    ///(or if it is not initialized or signaling failed).
+    pub fn signal_saturating(&self, count: u32) -> u32 {
+        if !self.is_init() {
+            return 0;
+        }
+
+        //Failure of increment alone is not reliable bound, as semaphore may not report overflow
+        let count = match self.headroom() {
+            Some(headroom) => count.min(headroom),
+            None => count,
+        };
+
+        let mut added = 0;
+        while added < count && self.signal_checked().is_ok() {
+            added += 1;
+        }
+
+        added
+    }
+
+    ///Decreases capacity by `count` permits, by acquiring them without ever releasing.
+    ///
+    ///If permits are held by other threads, shrinking below their number awaits until they are returned.
+    pub fn shrink(&self, count: u32) {
+        for _ in 0..count {
+            self.wait();
+        }
+    }
+
+    ///Attempts to decrease capacity by up to `count` permits, without blocking.
+    ///
+    ///Returns number of permits removed, which is less than `count` if not enough permits were available.
+    pub fn try_shrink(&self, count: u32) -> u32 {
+        let mut removed = 0;
+        while removed < count && self.try_wait() {
+            removed += 1;
+        }
+
+        removed
+    }
+}
@@ -0,0 +1,90 @@
+//Emulation of semaphore's maximum value for platforms which do not enforce it.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+//Maximum value indicating that ceiling is not tracked
+const UNTRACKED: u32 = u32::max_value();
+
+//Tracks number of permits, reserving it before semaphore is incremented, and releasing after it is decremented.
+//Hence tracked count is never below actual value of semaphore, and keeping it within maximum keeps semaphore within maximum too.
+pub(crate) struct Ceiling {
+    max: u32,
+    count: AtomicU32,
+}
+
+impl Ceiling {
+    #[inline(always)]
+    pub(crate) const fn untracked() -> Self {
+        Self::new(UNTRACKED)
+    }
+
+    #[inline(always)]
+    pub(crate) const fn new(max: u32) -> Self {
+        Self {
+            max,
+            count: AtomicU32::new(0),
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn max(&self) -> Option<u32> {
+        match self.max {
+            UNTRACKED => None,
+            max => Some(max),
+        }
+    }
+
+    #[cfg(all(feature = "counting", any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos")))]
+    #[inline(always)]
+    //Returns number of permits that can be added without exceeding maximum, if it is tracked
+    pub(crate) fn remaining(&self) -> Option<u32> {
+        match self.max {
+            UNTRACKED => None,
+            max => Some(max.saturating_sub(self.count.load(Ordering::Acquire))),
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn init(&self, init: u32) {
+        if self.max != UNTRACKED {
+            self.count.store(init, Ordering::Release);
+        }
+    }
+
+    #[inline(always)]
+    //Must be called after permit is acquired
+    pub(crate) fn release(&self) {
+        if self.max != UNTRACKED {
+            self.count.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    #[inline]
+    //Must be called before semaphore is incremented, returning `false` if it would exceed maximum
+    pub(crate) fn reserve(&self, num: u32) -> bool {
+        if self.max == UNTRACKED {
+            return true;
+        }
+
+        let mut count = self.count.load(Ordering::Acquire);
+        loop {
+            let new_count = match count.checked_add(num) {
+                Some(new_count) if new_count <= self.max => new_count,
+                _ => break false,
+            };
+
+            match self.count.compare_exchange_weak(count, new_count, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => break true,
+                Err(actual) => count = actual,
+            }
+        }
+    }
+
+    #[inline(always)]
+    //Reverts reservation, if semaphore failed to increment
+    pub(crate) fn unreserve(&self, num: u32) {
+        if self.max != UNTRACKED {
+            self.count.fetch_sub(num, Ordering::AcqRel);
+        }
+    }
+}
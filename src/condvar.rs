@@ -0,0 +1,151 @@
+//!Counting semaphore built on mutex and condition variable.
+//!
+//!Alternative to OS semaphore for long or repeatedly recomputed timeouts.
+//!Native POSIX `wait_timeout` uses `sem_timedwait`, which accepts absolute `CLOCK_REALTIME` deadline,
+//!hence it is affected by adjustments of system clock.
+//!This semaphore measures timeout via monotonic `Instant`, and, as waiters sleep on condition variable,
+//!it can wake all of them at once to re-check state (see `signal_many`).
+//!
+//!Tradeoff is that every operation takes mutex, which makes it slower than native semaphore under contention,
+//!and that it is not async-signal-safe, so it cannot be signaled from signal handler.
+
+use core::time;
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+///Counting semaphore based on `Mutex` and `Condvar`.
+pub struct Sem {
+    count: Mutex<u32>,
+    cond: Condvar,
+}
+
+impl Sem {
+    ///Maximum value of semaphore.
+    pub const MAX: u32 = u32::max_value();
+
+    ///Whether `wait_timeout` is supported.
+    pub const SUPPORTS_TIMEOUT: bool = true;
+
+    ///Granularity of timeout accepted by `wait_timeout`.
+    ///
+    ///Timeout is measured via `Instant`, which is nanosecond on supported platforms.
+    pub const TIMEOUT_RESOLUTION: time::Duration = time::Duration::from_nanos(1);
+
+    ///Creates new instance with `init` as initial value.
+    pub const fn new(init: u32) -> Self {
+        Self {
+            count: Mutex::new(init),
+            cond: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, u32> {
+        //Counter is never left in inconsistent state, so poison can be ignored
+        match self.count.lock() {
+            Ok(count) => count,
+            Err(error) => error.into_inner(),
+        }
+    }
+
+    #[inline]
+    ///Returns current value.
+    pub fn value(&self) -> u32 {
+        *self.lock()
+    }
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal.
+    pub fn wait(&self) {
+        let mut count = self.lock();
+        while *count == 0 {
+            count = match self.cond.wait(count) {
+                Ok(count) => count,
+                Err(error) => error.into_inner(),
+            };
+        }
+        *count -= 1;
+    }
+
+    ///Attempts to decrement self, returning whether self was signaled or not.
+    ///
+    ///Returns `true` if self was signaled.
+    ///
+    ///Returns `false` otherwise.
+    pub fn try_wait(&self) -> bool {
+        let mut count = self.lock();
+        match *count {
+            0 => false,
+            _ => {
+                *count -= 1;
+                true
+            }
+        }
+    }
+
+    ///Attempts to decrement self within provided time, returning whether self was signaled or not.
+    ///
+    ///Timeout is measured via monotonic clock, hence it is not affected by changes of system time.
+    ///
+    ///Returns `true` if self was signaled within specified timeout
+    ///
+    ///Returns `false` otherwise
+    pub fn wait_timeout(&self, timeout: time::Duration) -> bool {
+        let start = std::time::Instant::now();
+        let mut count = self.lock();
+        while *count == 0 {
+            let remaining = match timeout.checked_sub(start.elapsed()) {
+                Some(remaining) if remaining > time::Duration::from_secs(0) => remaining,
+                _ => return false,
+            };
+
+            count = match self.cond.wait_timeout(count, remaining) {
+                Ok((count, _)) => count,
+                Err(error) => error.into_inner().0,
+            };
+        }
+        *count -= 1;
+        true
+    }
+
+    #[inline]
+    ///Increments self, waking awaiting thread, if any.
+    pub fn signal(&self) {
+        let mut count = self.lock();
+        strict_assert_ne!(*count, Self::MAX, "Semaphore value overflow");
+        *count += 1;
+        self.cond.notify_one();
+    }
+
+    ///Increments self `count` times, waking all awaiting threads via single broadcast.
+    pub fn signal_many(&self, count: u32) {
+        let mut value = self.lock();
+        strict_assert!(value.checked_add(count).is_some(), "Semaphore value overflow");
+        *value = value.saturating_add(count);
+        self.cond.notify_all();
+    }
+}
+
+impl crate::Semaphore for Sem {
+    const SUPPORTS_TIMEOUT: bool = Sem::SUPPORTS_TIMEOUT;
+    const TIMEOUT_RESOLUTION: time::Duration = Sem::TIMEOUT_RESOLUTION;
+
+    #[inline(always)]
+    fn wait(&self) {
+        Sem::wait(self)
+    }
+
+    #[inline(always)]
+    fn try_wait(&self) -> bool {
+        Sem::try_wait(self)
+    }
+
+    #[inline(always)]
+    fn wait_timeout(&self, timeout: time::Duration) -> bool {
+        Sem::wait_timeout(self, timeout)
+    }
+
+    #[inline(always)]
+    fn signal(&self) {
+        Sem::signal(self)
+    }
+}
@@ -0,0 +1,62 @@
+use crate::Sem;
+
+impl Sem {
+    ///Decrements self, returning whether it had to block.
+    ///
+    ///Attempts to acquire permit without blocking first, falling back to `wait` on failure.
+    ///In either case exactly one permit is acquired.
+    ///
+    ///Returns `false` if self was signaled, and permit was acquired immediately.
+    ///
+    ///Returns `true` if thread had to await signal.
+    pub fn wait_observed(&self) -> bool {
+        if self.try_wait() {
+            false
+        } else {
+            self.wait();
+            true
+        }
+    }
+}
+
+impl Sem {
+    ///Decrements self, escalating from spinning to blocking wait.
+    ///
+    ///Waiting proceeds in phases, each attempting to acquire permit without blocking:
+    ///
+    ///1. Single attempt;
+    ///2. Up to `spin_budget` attempts separated by spin loop hint;
+    ///3. Up to `yield_budget` attempts separated by yielding CPU to scheduler (requires `std`, skipped otherwise);
+    ///4. Blocking `wait`.
+    ///
+    ///Permit is acquired exactly once, by whichever phase succeeds first.
+    ///
+    ///Spinning avoids cost of system call when permit is held for short time,
+    ///while yielding lets holder of permit to run when threads outnumber cores.
+    ///Both waste CPU once permit is held for longer, hence budgets should be kept small.
+    ///Recommended defaults are `spin_budget = 100` and `yield_budget = 10`, while `(0, 0)` is equivalent to `wait`.
+    pub fn wait_adaptive(&self, spin_budget: u32, yield_budget: u32) {
+        if self.try_wait() {
+            return;
+        }
+
+        for _ in 0..spin_budget {
+            core::hint::spin_loop();
+            if self.try_wait() {
+                return;
+            }
+        }
+
+        #[cfg(feature = "std")]
+        for _ in 0..yield_budget {
+            std::thread::yield_now();
+            if self.try_wait() {
+                return;
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = yield_budget;
+
+        self.wait();
+    }
+}
@@ -0,0 +1,63 @@
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///Semaphore operation error.
+pub enum SemError {
+    ///Semaphore is already initialized.
+    AlreadyInitialized,
+    ///Semaphore is not initialized.
+    Uninitialized,
+    ///Operation cannot be completed without blocking.
+    WouldBlock,
+    ///Wait timed out.
+    TimedOut,
+    ///Wait was interrupted (e.g. by signal).
+    Interrupted,
+    ///Operation would increase semaphore past its maximum value.
+    Overflow,
+    ///Timeout exceeds maximum supported by platform.
+    TimeoutOverflow,
+    ///Wait resulted in `WAIT_ABANDONED`, meaning handle is abandoned mutex rather than semaphore.
+    ///
+    ///Semaphores are never abandoned, so it indicates that wrong handle was passed (e.g. to `from_inherited_handle`).
+    Abandoned,
+    ///Underlying OS error, carrying raw platform code.
+    Os(i32),
+}
+
+impl SemError {
+    #[cfg(any(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))), target_os = "fuchsia"))]
+    #[cold]
+    pub(crate) fn from_errno(code: i32) -> Self {
+        if code == libc::EAGAIN || code == libc::EWOULDBLOCK {
+            SemError::WouldBlock
+        } else if code == libc::ETIMEDOUT {
+            SemError::TimedOut
+        } else if code == libc::EINTR {
+            SemError::Interrupted
+        } else if code == libc::EOVERFLOW {
+            SemError::Overflow
+        } else {
+            SemError::Os(code)
+        }
+    }
+}
+
+impl fmt::Display for SemError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SemError::AlreadyInitialized => fmt.write_str("Semaphore is already initialized"),
+            SemError::Uninitialized => fmt.write_str("Semaphore is not initialized"),
+            SemError::WouldBlock => fmt.write_str("Operation would block"),
+            SemError::TimedOut => fmt.write_str("Wait timed out"),
+            SemError::Interrupted => fmt.write_str("Wait interrupted"),
+            SemError::Overflow => fmt.write_str("Semaphore maximum value exceeded"),
+            SemError::TimeoutOverflow => fmt.write_str("Timeout exceeds platform maximum"),
+            SemError::Abandoned => fmt.write_str("Handle is abandoned mutex, not semaphore"),
+            SemError::Os(code) => fmt.write_fmt(format_args!("OS error: {}", code)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SemError {}
@@ -0,0 +1,184 @@
+use core::{mem, time};
+
+use error_code::ErrorCode;
+
+use crate::unlikely;
+
+///Semaphore backed by `eventfd` in semaphore mode, which can be polled as file descriptor.
+///
+///Every read from `eventfd` created with `EFD_SEMAPHORE` decrements its counter by one,
+///while write increments it, making descriptor readable whenever semaphore is signaled.
+///This allows to await semaphore within event loop (e.g. `epoll`) alongside sockets.
+///
+///## File descriptor lifecycle
+///
+///Descriptor is created non-blocking and close-on-exec in `new`, and it is closed on `Drop`.
+///It can be registered in reactor via `as_raw_fd`, but it must be deregistered before semaphore is dropped.
+///Once reactor reports it as readable, permit should be acquired with `try_wait`, as other thread may acquire it first.
+///
+///Only available on Linux and Android.
+pub struct EventfdSem {
+    fd: libc::c_int,
+}
+
+impl EventfdSem {
+    ///Creates new instance, initializing it with `init`
+    pub fn new(init: u32) -> Option<Self> {
+        let fd = unsafe {
+            libc::eventfd(init as _, libc::EFD_SEMAPHORE | libc::EFD_NONBLOCK | libc::EFD_CLOEXEC)
+        };
+
+        match fd {
+            -1 => unlikely(None),
+            fd => Some(Self {
+                fd
+            }),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns underlying file descriptor.
+    ///
+    ///Descriptor is owned by semaphore, so it must not be closed.
+    pub fn as_raw_fd(&self) -> libc::c_int {
+        self.fd
+    }
+
+    //Returns whether descriptor became readable within timeout, or -1 for infinite timeout
+    fn poll(&self, timeout_ms: libc::c_int) -> bool {
+        let mut fd = libc::pollfd {
+            fd: self.fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        loop {
+            let res = unsafe {
+                libc::poll(&mut fd, 1, timeout_ms)
+            };
+
+            match res {
+                -1 => {
+                    let errno = ErrorCode::last_posix();
+                    if errno.raw_code() != libc::EINTR {
+                        panic!("Unexpected error: {}", errno);
+                    }
+                },
+                0 => break false,
+                _ => break true,
+            }
+        }
+    }
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal.
+    pub fn wait(&self) {
+        while !self.try_wait() {
+            self.poll(-1);
+        }
+    }
+
+    ///Attempts to decrement self, returning whether self was signaled or not.
+    ///
+    ///Returns `true` if self was signaled.
+    ///
+    ///Returns `false` otherwise.
+    pub fn try_wait(&self) -> bool {
+        let mut value = mem::MaybeUninit::<u64>::uninit();
+        loop {
+            let res = unsafe {
+                libc::read(self.fd, value.as_mut_ptr() as *mut _, mem::size_of::<u64>())
+            };
+
+            if res == -1 {
+                let errno = ErrorCode::last_posix().raw_code();
+                if errno == libc::EAGAIN || errno == libc::EWOULDBLOCK {
+                    break false;
+                }
+
+                strict_assert_eq!(errno, libc::EINTR, "Unexpected error");
+                continue;
+            }
+
+            break true
+        }
+    }
+
+    ///Attempts to decrement self within provided time, returning whether self was signaled or not.
+    ///
+    ///Timeout is awaited with millisecond granularity, rounding it up.
+    ///
+    ///Returns `true` if self was signaled within specified timeout
+    ///
+    ///Returns `false` otherwise
+    pub fn wait_timeout(&self, timeout: time::Duration) -> bool {
+        let start = now();
+        loop {
+            if self.try_wait() {
+                break true;
+            }
+
+            let elapsed = now().saturating_sub(start);
+            let remaining = match timeout.checked_sub(elapsed) {
+                Some(remaining) if remaining > time::Duration::from_secs(0) => remaining,
+                _ => break false,
+            };
+
+            //Round up, so that wait doesn't end early
+            let remaining_ms = remaining.as_nanos().saturating_add(999_999) / 1_000_000;
+            let remaining_ms = match remaining_ms > libc::c_int::max_value() as u128 {
+                true => libc::c_int::max_value(),
+                false => remaining_ms as libc::c_int,
+            };
+            self.poll(remaining_ms);
+        }
+    }
+
+    #[inline]
+    ///Increments self, waking any awaiting thread as result.
+    pub fn signal(&self) {
+        self.signal_many(1);
+    }
+
+    ///Increments self `count` times, waking awaiting threads as result.
+    pub fn signal_many(&self, count: u32) {
+        let value = u64::from(count);
+        let res = unsafe {
+            libc::write(self.fd, &value as *const u64 as *const _, mem::size_of::<u64>())
+        };
+        strict_assert_eq!(res, mem::size_of::<u64>() as isize, "Semaphore value overflow");
+    }
+}
+
+//Returns monotonic time since unspecified point.
+fn now() -> time::Duration {
+    let mut time = mem::MaybeUninit::uninit();
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, time.as_mut_ptr()) } == -1 {
+        panic!("Failed to get current time");
+    }
+
+    let time = unsafe {
+        time.assume_init()
+    };
+    time::Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+}
+
+impl Drop for EventfdSem {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::os::unix::io::AsRawFd for EventfdSem {
+    #[inline(always)]
+    fn as_raw_fd(&self) -> std::os::unix::io::RawFd {
+        self.fd
+    }
+}
+
+unsafe impl Send for EventfdSem {}
+unsafe impl Sync for EventfdSem {}
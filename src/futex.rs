@@ -0,0 +1,182 @@
+//!Atomic counting semaphore, blocking via futex.
+//!
+//!Counter is plain `AtomicU32`, fully controlled by this crate, without `sem_t` indirection.
+//!On Linux and Android waiting thread is put to sleep with `futex` on the counter itself.
+//!
+//!On Windows, with `wait_on_address` feature, `WaitOnAddress` is used in the same way, which requires Windows 8 or later.
+//!Unlike [Sem](../struct.Sem.html), it requires no kernel object, hence creating semaphore is cheap and does not consume handles.
+//!
+//!On other platforms there is no futex, so waiting falls back to busy-wait,
+//!same as [atomic_counting::Sem](../atomic_counting/struct.Sem.html).
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod sys {
+    use core::sync::atomic::AtomicU32;
+    use core::ptr;
+
+    //Returns on wake, spurious wake or if `word` is not equal to `expected`
+    pub fn wait(word: &AtomicU32, expected: u32) {
+        unsafe {
+            libc::syscall(libc::SYS_futex, word.as_ptr(), libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG, expected, ptr::null::<libc::timespec>());
+        }
+    }
+
+    pub fn wake(word: &AtomicU32, count: i32) {
+        unsafe {
+            libc::syscall(libc::SYS_futex, word.as_ptr(), libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG, count);
+        }
+    }
+}
+
+#[cfg(all(windows, feature = "wait_on_address"))]
+mod sys {
+    use core::sync::atomic::AtomicU32;
+    use core::ffi::c_void;
+
+    const INFINITE: u32 = 0xFFFFFFFF;
+
+    #[link(name = "synchronization")]
+    extern "system" {
+        fn WaitOnAddress(address: *const c_void, compare: *const c_void, size: usize, timeout_ms: u32) -> i32;
+        fn WakeByAddressSingle(address: *const c_void);
+        fn WakeByAddressAll(address: *const c_void);
+    }
+
+    //Returns on wake, spurious wake or if `word` is not equal to `expected`
+    pub fn wait(word: &AtomicU32, expected: u32) {
+        unsafe {
+            WaitOnAddress(word.as_ptr() as *const c_void, &expected as *const u32 as *const c_void, core::mem::size_of::<u32>(), INFINITE);
+        }
+    }
+
+    pub fn wake(word: &AtomicU32, count: i32) {
+        unsafe {
+            match count {
+                1 => WakeByAddressSingle(word.as_ptr() as *const c_void),
+                _ => WakeByAddressAll(word.as_ptr() as *const c_void),
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", all(windows, feature = "wait_on_address"))))]
+mod sys {
+    use core::sync::atomic::AtomicU32;
+
+    #[inline(always)]
+    pub fn wait(_: &AtomicU32, _: u32) {
+        core::hint::spin_loop();
+    }
+
+    #[inline(always)]
+    pub fn wake(_: &AtomicU32, _: i32) {
+    }
+}
+
+///Counting semaphore based on atomic counter, blocking via futex.
+pub struct Sem {
+    count: AtomicU32,
+    //Number of threads about to sleep or sleeping on `count`, used to skip wake syscall
+    waiters: AtomicU32,
+}
+
+impl Sem {
+    ///Maximum value of semaphore.
+    pub const MAX: u32 = u32::max_value();
+
+    ///Whether `wait_timeout` is supported.
+    ///
+    ///There is no `wait_timeout` for this semaphore.
+    pub const SUPPORTS_TIMEOUT: bool = false;
+
+    ///Granularity of timeout, which is zero as timeout is not supported.
+    pub const TIMEOUT_RESOLUTION: core::time::Duration = core::time::Duration::from_nanos(0);
+
+    ///Creates new instance with `init` as initial value.
+    pub const fn new(init: u32) -> Self {
+        Self {
+            count: AtomicU32::new(init),
+            waiters: AtomicU32::new(0),
+        }
+    }
+
+    #[inline]
+    ///Returns current value.
+    pub fn value(&self) -> u32 {
+        self.count.load(Ordering::Acquire)
+    }
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise sleeps until signal.
+    pub fn wait(&self) {
+        while !self.try_wait() {
+            //Either `signal` observes us as waiter or futex observes incremented count
+            self.waiters.fetch_add(1, Ordering::SeqCst);
+            sys::wait(&self.count, 0);
+            self.waiters.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    ///Attempts to decrement self, returning whether self was signaled or not.
+    ///
+    ///Returns `true` if self was signaled.
+    ///
+    ///Returns `false` otherwise.
+    pub fn try_wait(&self) -> bool {
+        let mut count = self.count.load(Ordering::Relaxed);
+        while count > 0 {
+            match self.count.compare_exchange_weak(count, count - 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => count = actual,
+            }
+        }
+
+        false
+    }
+
+    ///Attempts to decrement self by `count` at once, returning whether it succeeded.
+    ///
+    ///Decrement is performed by single atomic operation, only if current value is at least `count`,
+    ///so permits are never partially acquired.
+    ///Such atomicity is available only for atomic based semaphores, not for OS backed [Sem](../struct.Sem.html).
+    ///
+    ///Returns `true` if `count` permits were acquired.
+    ///
+    ///Returns `false` otherwise, leaving value unchanged.
+    pub fn try_wait_many(&self, count: u32) -> bool {
+        let mut value = self.count.load(Ordering::Relaxed);
+        while value >= count {
+            match self.count.compare_exchange_weak(value, value - count, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => value = actual,
+            }
+        }
+
+        false
+    }
+
+    #[inline]
+    ///Increments self, waking up waiting thread, if any.
+    pub fn signal(&self) {
+        let count = self.count.fetch_add(1, Ordering::SeqCst);
+        strict_assert_ne!(count, Self::MAX, "Semaphore value overflow");
+
+        if self.waiters.load(Ordering::SeqCst) > 0 {
+            sys::wake(&self.count, 1);
+        }
+    }
+
+    #[inline]
+    ///Increments self `count` times, waking up waiting threads, if any.
+    pub fn signal_many(&self, count: u32) {
+        let prev = self.count.fetch_add(count, Ordering::SeqCst);
+        strict_assert!(prev.checked_add(count).is_some(), "Semaphore value overflow");
+
+        if self.waiters.load(Ordering::SeqCst) > 0 {
+            sys::wake(&self.count, i32::max_value());
+        }
+    }
+}
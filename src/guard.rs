@@ -0,0 +1,68 @@
+use crate::Sem;
+
+///Semaphore permit, which signals semaphore on drop.
+pub struct SemGuard<'a> {
+    sem: &'a Sem,
+}
+
+impl<'a> SemGuard<'a> {
+    #[inline(always)]
+    ///Creates guard over already acquired permit.
+    ///
+    ///Guard will signal `sem` on drop.
+    pub fn new(sem: &'a Sem) -> Self {
+        Self {
+            sem
+        }
+    }
+
+    #[inline(always)]
+    ///Returns semaphore this permit belongs to.
+    pub fn sem(&self) -> &'a Sem {
+        self.sem
+    }
+}
+
+impl Drop for SemGuard<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.sem.signal();
+    }
+}
+
+impl Sem {
+    ///Decrements self, returning guard that increments self on drop.
+    ///
+    ///Blocks until permit is available.
+    pub fn acquire(&self) -> SemGuard<'_> {
+        self.wait();
+        SemGuard::new(self)
+    }
+
+    #[inline]
+    ///Attempts to decrement self, returning guard that increments self on drop.
+    ///
+    ///Returns `None` if self is not signaled.
+    pub fn try_acquire(&self) -> Option<SemGuard<'_>> {
+        match self.try_wait() {
+            true => Some(SemGuard::new(self)),
+            false => None,
+        }
+    }
+
+    ///Returns iterator that yields permit on every `next()`, blocking until permit is available.
+    ///
+    ///Iterator never terminates on its own, so it is up to user to bound it (e.g. `take(n)`)
+    ///
+    ///## Usage
+    ///
+    ///```rust
+    ///let sem = semka::Sem::new(2).unwrap();
+    ///for _permit in sem.permits().take(4) {
+    ///    //do work while holding permit
+    ///}
+    ///```
+    pub fn permits(&self) -> impl Iterator<Item = SemGuard<'_>> {
+        core::iter::repeat_with(move || self.acquire())
+    }
+}
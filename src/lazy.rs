@@ -0,0 +1,81 @@
+use core::ops;
+
+use crate::Sem;
+
+///Semaphore that can be created in `const` context, initializing itself on first use.
+///
+///Removes need to pair `Sem::new_uninit` with manual `init` (or `OnceCell`-like wrapper) for `static` semaphores.
+///
+///## Usage
+///
+///```rust
+///static SEM: semka::ConstSem = semka::ConstSem::new(1);
+///
+///assert!(SEM.try_wait());
+///assert!(!SEM.try_wait());
+///SEM.signal();
+///```
+pub struct ConstSem {
+    sem: Sem,
+    init: u32,
+}
+
+impl ConstSem {
+    ///Creates new instance, which will be initialized with `init` on first use.
+    pub const fn new(init: u32) -> Self {
+        Self {
+            sem: unsafe {
+                Sem::new_uninit()
+            },
+            init,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns reference to underlying semaphore, initializing it if necessary.
+    ///
+    ///## Panics
+    ///
+    ///If initialization fails.
+    pub fn get(&self) -> &Sem {
+        self.sem.get_or_init(self.init)
+    }
+}
+
+impl Sem {
+    #[cold]
+    #[inline(never)]
+    fn init_or_panic(&self, init: u32) {
+        //On concurrent init, losing thread awaits and observes semaphore initialized by winner.
+        if !self.init(init) && !self.is_init() {
+            panic!("Failed to initialize semaphore");
+        }
+    }
+
+    ///Initializes semaphore with `init`, unless it is already initialized, returning reference to self.
+    ///
+    ///If another thread is in the middle of initialization, awaits for it to finish.
+    ///Failed initialization leaves semaphore uninitialized, so next call attempts it again.
+    ///
+    ///Intended for semaphores created via `new_uninit`, providing `OnceCell`-like initialization.
+    ///
+    ///## Panics
+    ///
+    ///If initialization fails.
+    pub fn get_or_init(&self, init: u32) -> &Self {
+        if !self.is_init() {
+            self.init_or_panic(init);
+        }
+
+        self
+    }
+}
+
+impl ops::Deref for ConstSem {
+    type Target = Sem;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
@@ -16,13 +16,89 @@
 //!
 //!This includes all `unix` targets and `fuchsia`
 //!
+//!Solaris and illumos (e.g. SmartOS, OmniOS) use the same implementation, as their `sem_timedwait`
+//!follows POSIX: absolute `CLOCK_REALTIME` deadline and `ETIMEDOUT` on timeout.
+//!CI only verifies that illumos target builds.
+//!
+//!Redox uses the same implementation on top of `relibc`, which provides POSIX semaphores.
+//!CI only verifies that Redox target builds.
+//!
+//!Android uses the same implementation on top of bionic.
+//!`sem_timedwait` is used with `CLOCK_REALTIME` deadline, which is supported by all API levels,
+//!while `sem_clockwait` requires API level 30 and therefore is not used.
+//!As consequence, timeouts are affected by changes of wall clock, same as on other POSIX systems.
+//!CI only verifies that Android target builds, timeout tests can be run on emulator.
+//!
 //!### Mac
 //!
 //!Uses `mach` API.
+//!
+//!This includes macOS, iOS (including simulator), tvOS and watchOS.
+//!tvOS and watchOS are tier 3 targets, requiring `build-std`, hence they are not checked by CI.
+//!
+//!### Miri
+//!
+//!OS semaphores rely on FFI, which Miri cannot execute.
+//![atomic_counting](atomic_counting/index.html), [spin](spin/index.html) and [parking](parking/index.html)
+//!are pure Rust and Miri-clean, which is verified by `tests/miri.rs` suite
+//!(`cargo +nightly miri test --all-features --test miri`).
+//!
+//!## Lifecycle
+//!
+//!All platform implementations follow the same state transitions:
+//!
+//!- `new_uninit` creates uninitialized semaphore;
+//!- `init` transitions uninitialized semaphore into initialized, failing if it is already initialized;
+//!- `get_or_init` performs `init` unless semaphore is already initialized, awaiting concurrent `init` to finish;
+//!- `close` transitions initialized semaphore back into uninitialized, doing nothing if it is not initialized;
+//!- `Drop` performs `close`, unless semaphore is wrapped into [ManualClose](struct.ManualClose.html).
+//!
+//!## Features
+//!
+//!Minimal build (`default-features = false`) provides only `Sem`, depending on neither `std` nor `alloc`.
+//!Adding `binary` or `counting` (e.g. `--no-default-features --features binary`) keeps the same guarantee,
+//!as semaphores never allocate, which makes it suitable for environments without heap.
+//!Only `std` and features enabling it link `std`.
+//!
+//!- `binary` - Enables [BinarySem](struct.BinarySem.html). Enabled by default.
+//!- `counting` - Enables components built on counting semaphore: [ConcurrencyLimiter](struct.ConcurrencyLimiter.html),
+//![PermitPool](struct.PermitPool.html), [BoundedCounter](struct.BoundedCounter.html) and `Sem` methods adjusting capacity (`grow`, `shrink`). Enabled by default.
+//![RwSem](struct.RwSem.html) requires both `binary` and `counting`.
+//!
+//!`Sem` itself is always available, as both interfaces are built on it.
+//!These features only control wrappers over `Sem`, whose own API is compiled regardless,
+//!hence disabling both leaves plain `Sem` as the minimal build.
+//!
+//!- `std` - Enables [MockSem](struct.MockSem.html) for testing code generic over [Semaphore](trait.Semaphore.html)
+//!, `wait_timeout` for [atomic_counting::Sem](atomic_counting/struct.Sem.html)
+//!, [parking::Sem](parking/struct.Sem.html) blocking via thread parking
+//!, `Sem` methods measuring time (e.g. `wait_timeout_remaining`)
+//!, [BoundedPool](struct.BoundedPool.html) limiting number of concurrently running threads (requires `counting` too)
+//!, poisoning of [BinarySem](struct.BinarySem.html) when thread panics while holding its lock (requires `binary` too)
+//!and implementations of `AsRawHandle` for Windows `Sem` and `AsRawFd` for [EventfdSem](struct.EventfdSem.html).
+//!POSIX `Sem` is in-process `sem_t` without file descriptor, hence it doesn't implement `AsRawFd`.
+//!- `strict` - Checks against misuse (e.g. failure to signal semaphore) in release builds too, panicking on violation.
+//!By default they are only performed in debug builds.
+//!- `lean_assert` - Reports failed misuse checks with static message, without formatting its operands,
+//!calling hook installed via [assert::set_hook](assert/fn.set_hook.html) (e.g. to abort) before panicking.
+//!It avoids formatting machinery on constrained targets, and allows to fail without unwinding (e.g. in signal handler).
+//!Release builds without `strict` perform no checks either way.
+//!- `metrics` - Enables `std` and `Sem::wait_timed`, returning time spent blocked, to be recorded by user's metrics of choice.
+//!- `condvar` - Enables `std` and [condvar::Sem](condvar/struct.Sem.html), semaphore based on mutex and condition variable,
+//!with timeout measured by monotonic clock.
+//!- `async` - Enables `std` and [async_counting::Sem](async_counting/struct.Sem.html), atomic counting semaphore with asynchronous wait.
+//!- `futex` - Enables [futex::Sem](futex/struct.Sem.html), atomic counting semaphore blocking via futex on Linux and Android.
+//!- `wait_on_address` - Enables `futex` and makes [futex::Sem](futex/struct.Sem.html) block via `WaitOnAddress` on Windows.
+//!Requires Windows 8 or later.
 
 #![no_std]
 #![warn(missing_docs)]
-#![cfg_attr(feature = "cargo-clippy", allow(clippy::style))]
+//Equivalent of former `cfg_attr(feature = "cargo-clippy", allow(clippy::style))`, as clippy no longer sets this feature
+//and tool lints need no cfg.
+#![allow(clippy::style)]
+
+#[cfg(feature = "std")]
+extern crate std;
 
 #[cold]
 #[inline(never)]
@@ -30,12 +106,87 @@ fn unlikely<T>(result: T) -> T {
     result
 }
 
+//Assertions against misuse, which are checked in debug builds or when `strict` feature is enabled.
+//
+//With `lean_assert` feature failure is reported via `assert::failed` with static message, without formatting operands.
+#[cfg(not(feature = "lean_assert"))]
+macro_rules! strict_assert {
+    ($($arg:tt)*) => {
+        if cfg!(any(debug_assertions, feature = "strict")) {
+            assert!($($arg)*);
+        }
+    };
+}
+
+#[cfg(feature = "lean_assert")]
+macro_rules! strict_assert {
+    ($cond:expr) => {
+        strict_assert!($cond, concat!("assertion failed: ", stringify!($cond)))
+    };
+    ($cond:expr, $msg:expr) => {
+        if cfg!(any(debug_assertions, feature = "strict")) && !$cond {
+            $crate::assert::failed($msg);
+        }
+    };
+}
+
+#[cfg(not(feature = "lean_assert"))]
+#[allow(unused_macros)]
+macro_rules! strict_assert_eq {
+    ($($arg:tt)*) => {
+        if cfg!(any(debug_assertions, feature = "strict")) {
+            assert_eq!($($arg)*);
+        }
+    };
+}
+
+#[cfg(feature = "lean_assert")]
+#[allow(unused_macros)]
+macro_rules! strict_assert_eq {
+    ($left:expr, $right:expr) => {
+        strict_assert_eq!($left, $right, concat!("assertion failed: ", stringify!($left), " == ", stringify!($right)))
+    };
+    ($left:expr, $right:expr, $msg:expr) => {
+        if cfg!(any(debug_assertions, feature = "strict")) && $left != $right {
+            $crate::assert::failed($msg);
+        }
+    };
+}
+
+#[cfg(not(feature = "lean_assert"))]
+#[allow(unused_macros)]
+macro_rules! strict_assert_ne {
+    ($($arg:tt)*) => {
+        if cfg!(any(debug_assertions, feature = "strict")) {
+            assert_ne!($($arg)*);
+        }
+    };
+}
+
+#[cfg(feature = "lean_assert")]
+#[allow(unused_macros)]
+macro_rules! strict_assert_ne {
+    ($left:expr, $right:expr) => {
+        strict_assert_ne!($left, $right, concat!("assertion failed: ", stringify!($left), " != ", stringify!($right)))
+    };
+    ($left:expr, $right:expr, $msg:expr) => {
+        if cfg!(any(debug_assertions, feature = "strict")) && $left == $right {
+            $crate::assert::failed($msg);
+        }
+    };
+}
+
+#[cfg(feature = "lean_assert")]
+pub mod assert;
+
 #[cfg(not(any(windows, unix, target_os = "fuchsia")))]
 compile_error!("Semaphore is not available for your target");
 
-#[cfg(any(all(unix, not(any(target_os = "macos", target_os = "ios"))), target_os = "fuchsia"))]
+#[cfg(not(windows))]
+mod ceiling;
+#[cfg(any(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))), target_os = "fuchsia"))]
 mod posix;
-#[cfg(any(all(unix, not(any(target_os = "macos", target_os = "ios"))), target_os = "fuchsia"))]
+#[cfg(any(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))), target_os = "fuchsia"))]
 pub use posix::Sem;
 
 #[cfg(windows)]
@@ -43,7 +194,74 @@ mod win32;
 #[cfg(windows)]
 pub use win32::Sem;
 
-#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
 mod mac;
-#[cfg(any(target_os = "macos", target_os = "ios"))]
-pub use mac::Sem;
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+pub use mac::{Sem, SyncPolicy};
+
+mod error;
+pub use error::SemError;
+mod builder;
+pub use builder::SemBuilder;
+mod timeout;
+pub use timeout::{TimeoutPolicy, WaitOutcome};
+mod semaphore;
+pub use semaphore::Semaphore;
+mod guard;
+pub use guard::SemGuard;
+mod cancel;
+mod contention;
+#[cfg(feature = "counting")]
+mod capacity;
+#[cfg(feature = "binary")]
+mod binary;
+#[cfg(feature = "binary")]
+pub use binary::{BinarySem, BinaryLock};
+mod lazy;
+pub use lazy::ConstSem;
+mod manual;
+pub use manual::ManualClose;
+mod notify;
+pub use notify::Notify;
+#[cfg(all(feature = "binary", feature = "counting"))]
+mod rw;
+#[cfg(all(feature = "binary", feature = "counting"))]
+pub use rw::{RwSem, RwReadGuard, RwWriteGuard};
+#[cfg(feature = "counting")]
+mod limiter;
+#[cfg(feature = "counting")]
+pub use limiter::ConcurrencyLimiter;
+#[cfg(feature = "counting")]
+mod pool;
+#[cfg(feature = "counting")]
+pub use pool::{PermitPool, PoolPermit};
+#[cfg(feature = "counting")]
+mod bounded;
+#[cfg(feature = "counting")]
+pub use bounded::{BoundedCounter, EmptySlot, FilledSlot};
+#[cfg(feature = "std")]
+mod mock;
+#[cfg(feature = "std")]
+pub use mock::MockSem;
+#[cfg(feature = "std")]
+mod timed;
+#[cfg(all(feature = "std", feature = "counting"))]
+mod bounded_pool;
+#[cfg(all(feature = "std", feature = "counting"))]
+pub use bounded_pool::BoundedPool;
+#[cfg(not(windows))]
+mod multi;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod eventfd;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use eventfd::EventfdSem;
+pub mod spin;
+pub mod atomic_counting;
+#[cfg(feature = "std")]
+pub mod parking;
+#[cfg(feature = "async")]
+pub mod async_counting;
+#[cfg(feature = "condvar")]
+pub mod condvar;
+#[cfg(feature = "futex")]
+pub mod futex;
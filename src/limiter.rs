@@ -0,0 +1,35 @@
+use crate::Sem;
+
+///Limits number of concurrently running tasks.
+///
+///Every task runs while holding [permit](struct.SemGuard.html), which is released once task returns or panics.
+pub struct ConcurrencyLimiter {
+    sem: Sem,
+}
+
+impl ConcurrencyLimiter {
+    ///Creates new instance, allowing at most `limit` concurrent tasks.
+    pub fn new(limit: u32) -> Option<Self> {
+        Sem::new(limit).map(|sem| Self {
+            sem
+        })
+    }
+
+    #[inline(always)]
+    ///Returns underlying semaphore.
+    pub fn sem(&self) -> &Sem {
+        &self.sem
+    }
+
+    ///Runs `task`, awaiting until there is free slot.
+    pub fn run<R, F: FnOnce() -> R>(&self, task: F) -> R {
+        let _permit = self.sem.acquire();
+        task()
+    }
+
+    ///Runs `task` if there is free slot, otherwise returns `None`.
+    pub fn try_run<R, F: FnOnce() -> R>(&self, task: F) -> Option<R> {
+        let _permit = self.sem.try_acquire()?;
+        Some(task())
+    }
+}
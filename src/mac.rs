@@ -2,7 +2,8 @@ use core::ffi::c_void;
 use core::{ptr, mem};
 use core::sync::atomic::{AtomicPtr, Ordering};
 
-use crate::unlikely;
+use crate::{unlikely, SemError, TimeoutPolicy};
+use crate::ceiling::Ceiling;
 
 #[repr(C)]
 struct TimeSpec {
@@ -10,19 +11,67 @@ struct TimeSpec {
     tv_nsec: libc::c_int,
 }
 
+//mach_timespec_t is pair of 32bit integers
+const _: () = assert!(mem::size_of::<TimeSpec>() == 8);
+const _: () = assert!(mem::align_of::<TimeSpec>() == 4);
+
+//semaphore_timedwait() expects relative timeout, with nanoseconds below 1s.
+//
+//`Duration` always carries excess nanoseconds into seconds, so `subsec_nanos` is already in range.
+//Timeout that doesn't fit `tv_sec` saturates to maximum representable timeout.
 impl Into<TimeSpec> for core::time::Duration {
     fn into(self) -> TimeSpec {
         use core::convert::TryFrom;
 
-        TimeSpec {
-            tv_sec: libc::c_uint::try_from(self.as_secs()).unwrap_or(libc::c_uint::max_value()),
-            tv_nsec: libc::c_int::try_from(self.subsec_nanos()).unwrap_or(libc::c_int::max_value())
+        match libc::c_uint::try_from(self.as_secs()) {
+            Ok(tv_sec) => TimeSpec {
+                tv_sec,
+                tv_nsec: self.subsec_nanos() as libc::c_int,
+            },
+            Err(_) => TimeSpec {
+                tv_sec: libc::c_uint::max_value(),
+                tv_nsec: 999_999_999,
+            },
         }
     }
 }
 
+const KERN_ABORTED: libc::c_int = 14;
 const KERN_OPERATION_TIMED_OUT: libc::c_int = 49;
-const SYNC_POLICY_FIFO: libc::c_int = 0;
+//Sentinel handle, indicating that semaphore is being created
+const INITING: *mut c_void = 1 as *mut c_void;
+const SYNC_POLICY_FIFO: libc::c_int = 0x0;
+const SYNC_POLICY_LIFO: libc::c_int = 0x2;
+const SYNC_POLICY_PREPOST: libc::c_int = 0x4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///Policy of mach semaphore, determining order in which waiters are woken up.
+pub enum SyncPolicy {
+    ///Waiters are woken up in order of arrival (`SYNC_POLICY_FIFO`).
+    Fifo,
+    ///Most recent waiter is woken up first (`SYNC_POLICY_LIFO`).
+    Lifo,
+    ///Signal with no waiters is remembered by the kernel, to be consumed by next waiter (`SYNC_POLICY_PREPOST`).
+    Prepost,
+}
+
+impl SyncPolicy {
+    #[inline(always)]
+    fn raw(self) -> libc::c_int {
+        match self {
+            SyncPolicy::Fifo => SYNC_POLICY_FIFO,
+            SyncPolicy::Lifo => SYNC_POLICY_LIFO,
+            SyncPolicy::Prepost => SYNC_POLICY_PREPOST,
+        }
+    }
+}
+
+impl Default for SyncPolicy {
+    #[inline(always)]
+    fn default() -> Self {
+        SyncPolicy::Fifo
+    }
+}
 
 extern "C" {
     static mach_task_self_: libc::c_uint;
@@ -38,23 +87,52 @@ extern "C" {
 
 ///MacOS semaphore based on mach API
 pub struct Sem {
-    handle: AtomicPtr<c_void>
+    handle: AtomicPtr<c_void>,
+    ceiling: Ceiling,
 }
 
+//semaphore_t is pointer
+const _: () = assert!(mem::size_of::<AtomicPtr<c_void>>() == mem::size_of::<*mut c_void>());
+
 impl Sem {
+    ///Maximum value of semaphore.
+    ///
+    ///Corresponds to maximum of `int` value accepted by `semaphore_create`.
+    pub const MAX: u32 = libc::c_int::max_value() as u32;
+
+    ///Whether `wait_timeout` is supported.
+    pub const SUPPORTS_TIMEOUT: bool = true;
+
+    ///Granularity of timeout accepted by `wait_timeout`.
+    ///
+    ///`semaphore_timedwait` accepts `mach_timespec_t`, hence it is nanosecond, but actual precision is subject to OS scheduler.
+    pub const TIMEOUT_RESOLUTION: core::time::Duration = core::time::Duration::from_nanos(1);
+
     ///Creates new uninit instance.
     ///
     ///It is UB to use it until `init` is called.
     pub const unsafe fn new_uninit() -> Self {
         Self {
-            handle: AtomicPtr::new(ptr::null_mut())
+            handle: AtomicPtr::new(ptr::null_mut()),
+            ceiling: Ceiling::untracked(),
         }
     }
 
     #[inline(always)]
     ///Returns whether semaphore is successfully initialized
     pub fn is_init(&self) -> bool {
-        !self.handle.load(Ordering::Acquire).is_null()
+        let handle = self.handle.load(Ordering::Acquire);
+        !handle.is_null() && handle != INITING
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn await_init(&self) {
+        while self.handle.load(Ordering::Acquire) == INITING {
+            unsafe {
+                libc::sched_yield();
+            }
+        }
     }
 
     #[must_use]
@@ -63,32 +141,47 @@ impl Sem {
     ///Returns `true` on success.
     ///
     ///Returns `false` if semaphore is already initialized or initialization failed.
+    ///
+    ///Initialization fails if `init` is greater than maximum, which is `Sem::MAX` unless semaphore is created with `new_with_max`.
     pub fn init(&self, init: u32) -> bool {
+        self.init_with_policy(init, SyncPolicy::default())
+    }
+
+    fn init_with_policy(&self, init: u32, policy: SyncPolicy) -> bool {
+        if init > self.max() {
+            return unlikely(false);
+        }
+
         if !self.handle.load(Ordering::Acquire).is_null() {
             //Similarly to `Once` we give priority to already-init path
             return false;
-        } else {
-            let mut handle = mem::MaybeUninit::uninit();
+        }
 
-            let res = unsafe {
-                semaphore_create(mach_task_self_, handle.as_mut_ptr(), SYNC_POLICY_FIFO, init as libc::c_int)
-            };
+        //Only thread that claims semaphore creates handle, others await it
+        match self.handle.compare_exchange(ptr::null_mut(), INITING, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => {
+                let mut handle = mem::MaybeUninit::uninit();
+                let res = unsafe {
+                    semaphore_create(mach_task_self_, handle.as_mut_ptr(), policy.raw(), init as libc::c_int)
+                };
 
-            let res = match res {
-                0 => unsafe {
-                    let handle = handle.assume_init();
-                    match self.handle.compare_exchange(ptr::null_mut(), handle, Ordering::SeqCst, Ordering::Acquire) {
-                        Ok(_) => true,
-                        Err(_) => {
-                            semaphore_destroy(mach_task_self_, handle);
-                            false
-                        }
+                match res {
+                    0 => {
+                        self.ceiling.init(init);
+                        self.handle.store(unsafe { handle.assume_init() }, Ordering::Release);
+                        true
+                    },
+                    _ => {
+                        self.handle.store(ptr::null_mut(), Ordering::Release);
+                        unlikely(false)
                     }
-                },
-                _ => false,
-            };
-
-            unlikely(res)
+                }
+            },
+            Err(handle) if handle == INITING => {
+                self.await_init();
+                unlikely(false)
+            },
+            Err(_) => unlikely(false),
         }
     }
 
@@ -105,6 +198,65 @@ impl Sem {
         }
     }
 
+    ///Creates new instance with specified `policy`, initializing it with `init`
+    ///
+    ///`new` and `init` use `SyncPolicy::Fifo`.
+    pub fn new_with_policy(init: u32, policy: SyncPolicy) -> Option<Self> {
+        Self::with_options(init, Self::MAX, policy)
+    }
+
+    ///Creates new instance, initializing it with `init`, which value never exceeds `max`.
+    ///
+    ///Mach semaphores do not support maximum value, hence it is emulated using counter of permits,
+    ///which is updated on every increment and successful decrement, adding overhead of atomic operation.
+    ///
+    ///Returns `None` if `max` is zero, below `init` or above `Sem::MAX`, or initialization fails.
+    pub fn new_with_max(init: u32, max: u32) -> Option<Self> {
+        Self::with_options(init, max, SyncPolicy::default())
+    }
+
+    pub(crate) fn with_options(init: u32, max: u32, policy: SyncPolicy) -> Option<Self> {
+        if max == 0 || init > max || max > Self::MAX {
+            return unlikely(None);
+        }
+
+        let result = Self {
+            handle: AtomicPtr::new(ptr::null_mut()),
+            ceiling: match max {
+                Self::MAX => Ceiling::untracked(),
+                max => Ceiling::new(max),
+            },
+        };
+
+        if result.init_with_policy(init, policy) {
+            Some(result)
+        } else {
+            unlikely(None)
+        }
+    }
+
+    #[inline]
+    ///Returns maximum value of semaphore.
+    pub fn max(&self) -> u32 {
+        self.ceiling.max().unwrap_or(Self::MAX)
+    }
+
+    #[cfg(feature = "counting")]
+    #[inline(always)]
+    //Number of permits that can be added without exceeding maximum
+    pub(crate) fn headroom(&self) -> Option<u32> {
+        self.ceiling.remaining()
+    }
+
+    #[inline(always)]
+    ///Always returns `None`.
+    ///
+    ///Mach semaphore is destroyed by its owner, making any other reference to it dangling.
+    ///Share it by reference (e.g. `Arc<Sem>`) instead.
+    pub fn duplicate(&self) -> Option<Self> {
+        None
+    }
+
     ///Decrements self, returning immediately if it was signaled.
     ///
     ///Otherwise awaits for signal.
@@ -113,7 +265,66 @@ impl Sem {
             semaphore_wait(self.handle.load(Ordering::Acquire))
         };
 
-        debug_assert_eq!(result, 0, "semaphore_wait() failed");
+        strict_assert_eq!(result, 0, "semaphore_wait() failed");
+        if result == 0 {
+            self.ceiling.release();
+        }
+    }
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal.
+    ///
+    ///Unlike `wait`, returns error on any failure other than interruption (`KERN_ABORTED`).
+    ///OS error code is mach's `kern_return_t`.
+    pub fn wait_checked(&self) -> Result<(), SemError> {
+        if !self.is_init() {
+            return Err(SemError::Uninitialized);
+        }
+
+        loop {
+            let result = unsafe {
+                semaphore_wait(self.handle.load(Ordering::Acquire))
+            };
+
+            match result {
+                0 => {
+                    self.ceiling.release();
+                    break Ok(());
+                },
+                KERN_ABORTED => continue,
+                code => break Err(SemError::Os(code)),
+            }
+        }
+    }
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal.
+    ///
+    ///Returns number of times wait was interrupted (`KERN_ABORTED`) and retried.
+    ///This is diagnostic information only and should be treated as best-effort.
+    pub fn wait_counting_interrupts(&self) -> u32 {
+        let mut interrupts = 0u32;
+        loop {
+            let result = unsafe {
+                semaphore_wait(self.handle.load(Ordering::Acquire))
+            };
+
+            match result {
+                KERN_ABORTED => {
+                    interrupts = interrupts.saturating_add(1);
+                    continue;
+                },
+                result => {
+                    strict_assert_eq!(result, 0, "semaphore_wait() failed");
+                    if result == 0 {
+                        self.ceiling.release();
+                    }
+                    break interrupts;
+                }
+            }
+        }
     }
 
     #[inline]
@@ -123,38 +334,187 @@ impl Sem {
     ///
     ///Returns `false` otherwise.
     pub fn try_wait(&self) -> bool {
-        self.wait_timeout(core::time::Duration::from_secs(0))
+        self.timed_wait(core::time::Duration::from_secs(0))
+    }
+
+    ///Attempts to decrement self, returning whether self was signaled or not.
+    ///
+    ///Unlike `try_wait`, distinguishes empty semaphore from failure.
+    ///
+    ///Returns `Ok(true)` if self was signaled.
+    ///
+    ///Returns `Ok(false)` if self was not signaled (`KERN_OPERATION_TIMED_OUT`).
+    ///
+    ///Returns `Err` on any other error.
+    pub fn try_wait_checked(&self) -> Result<bool, SemError> {
+        if !self.is_init() {
+            return Err(SemError::Uninitialized);
+        }
+
+        let result = unsafe {
+            semaphore_timedwait(self.handle.load(Ordering::Acquire), core::time::Duration::from_secs(0).into())
+        };
+
+        match result {
+            0 => {
+                self.ceiling.release();
+                Ok(true)
+            },
+            KERN_OPERATION_TIMED_OUT => Ok(false),
+            KERN_ABORTED => Err(SemError::Interrupted),
+            code => Err(SemError::Os(code)),
+        }
     }
 
     ///Attempts to decrement self within provided time, returning whether self was signaled or not.
     ///
+    ///Mach timeout is relative, hence it is not affected by changes of wall clock.
+    ///Timeouts longer than `u32::MAX` seconds are saturated.
+    ///
     ///Returns `true` if self was signaled within specified timeout
     ///
     ///Returns `false` otherwise
+    ///
+    ///Zero timeout is equivalent to `try_wait`.
     pub fn wait_timeout(&self, timeout: core::time::Duration) -> bool {
+        if timeout == core::time::Duration::from_secs(0) {
+            return self.try_wait();
+        }
+
+        self.timed_wait(timeout)
+    }
+
+    fn timed_wait(&self, timeout: core::time::Duration) -> bool {
         let result = unsafe {
             semaphore_timedwait(self.handle.load(Ordering::Acquire), timeout.into())
         };
 
-        debug_assert!(result == 0 || result == KERN_OPERATION_TIMED_OUT, "semaphore_timedwait() failed");
-        result == 0
+        strict_assert!(result == 0 || result == KERN_OPERATION_TIMED_OUT, "semaphore_timedwait() failed");
+        if result == 0 {
+            self.ceiling.release();
+            true
+        } else {
+            false
+        }
+    }
+
+    ///Attempts to decrement self within provided time, returning whether self was signaled or not.
+    ///
+    ///Mach timeout is limited to `u32::MAX` seconds, otherwise `policy` is applied.
+    ///
+    ///Returns `Ok(true)` if self was signaled within specified timeout
+    ///
+    ///Returns `Ok(false)` otherwise
+    ///
+    ///Returns `Err(SemError::TimeoutOverflow)` if timeout overflows and `policy` is `TimeoutPolicy::Error`
+    pub fn wait_timeout_with_policy(&self, timeout: core::time::Duration, policy: TimeoutPolicy) -> Result<bool, SemError> {
+        if timeout.as_secs() <= libc::c_uint::max_value() as u64 {
+            return Ok(self.wait_timeout(timeout));
+        }
+
+        match policy {
+            TimeoutPolicy::Saturate => Ok(self.wait_timeout(timeout)),
+            TimeoutPolicy::Error => Err(SemError::TimeoutOverflow),
+            TimeoutPolicy::Infinite => {
+                self.wait();
+                Ok(true)
+            },
+        }
     }
 
     ///Increments self, waking any awaiting thread as result.
     pub fn signal(&self) {
+        let reserved = self.ceiling.reserve(1);
+        strict_assert!(reserved, "Semaphore value would exceed its maximum");
+        if !reserved {
+            return;
+        }
+
         let res = unsafe {
             semaphore_signal(self.handle.load(Ordering::Acquire))
         };
+        if res != 0 {
+            self.ceiling.unreserve(1);
+        }
 
-        debug_assert_eq!(res, 0, "semaphore_signal() failed");
+        strict_assert_eq!(res, 0, "semaphore_signal() failed");
+    }
+
+    #[inline]
+    ///Increments self, waking any awaiting thread as result.
+    ///
+    ///Performs only `semaphore_signal` trap without any assertions or panics,
+    ///except for atomic update of emulated maximum, if any,
+    ///so unlike `signal` it can be used within signal handler to wake up awaiting thread.
+    pub fn signal_from_handler(&self) {
+        if self.ceiling.reserve(1) {
+            let res = unsafe {
+                semaphore_signal(self.handle.load(Ordering::Acquire))
+            };
+            if res != 0 {
+                self.ceiling.unreserve(1);
+            }
+        }
+    }
+
+    ///Increments self, waking any awaiting thread as result.
+    ///
+    ///Unlike `signal`, reports failure to increment, in which case permit is not added.
+    ///
+    ///Mach semaphores do not report overflow, hence `SemError::Overflow` is only returned for emulated maximum.
+    pub fn signal_checked(&self) -> Result<(), SemError> {
+        if !self.is_init() {
+            return Err(SemError::Uninitialized);
+        } else if !self.ceiling.reserve(1) {
+            return Err(SemError::Overflow);
+        }
+
+        let res = unsafe {
+            semaphore_signal(self.handle.load(Ordering::Acquire))
+        };
+
+        match res {
+            0 => Ok(()),
+            code => {
+                self.ceiling.unreserve(1);
+                Err(SemError::Os(code))
+            },
+        }
+    }
+
+    ///Increments self `count` times, waking awaiting threads as result.
+    pub fn signal_many(&self, count: u32) {
+        strict_assert!(count <= self.max(), "Semaphore value would exceed its maximum");
+
+        for _ in 0..count {
+            self.signal();
+        }
+    }
+
+    #[inline]
+    ///Consumes self without performing deinitialization.
+    ///
+    ///Semaphore is never destroyed, keeping it alive until task exits.
+    ///Cleanup becomes responsibility of whoever owns underlying semaphore, if anyone.
+    ///As `Drop` is not run, there is no risk of closing semaphore twice.
+    pub fn leak(self) {
+        core::mem::forget(self);
     }
 
     ///Performs deinitialization.
     ///
     ///Using `Sem` after `close` is undefined behaviour, unless `init` is called
+    ///
+    ///Semaphore returns to uninitialized state, after which `is_init` returns `false` and `init` can be called again.
+    ///Closing uninitialized semaphore does nothing, so `close` can be safely called multiple times.
     pub unsafe fn close(&self) {
-        let handle = self.handle.swap(ptr::null_mut(), Ordering::AcqRel);
-        if !handle.is_null() {
+        //Similarly to POSIX, semaphore which is being initialized is not initialized yet, so it is left alone
+        let handle = self.handle.load(Ordering::Acquire);
+        if handle.is_null() || handle == INITING {
+            return;
+        }
+
+        if self.handle.compare_exchange(handle, ptr::null_mut(), Ordering::AcqRel, Ordering::Relaxed).is_ok() {
             semaphore_destroy(mach_task_self_, handle);
         }
     }
@@ -0,0 +1,49 @@
+use core::{mem, ops};
+
+///Wrapper suppressing automatic deinitialization on drop.
+///
+///Semaphore wrapped into it is never closed automatically, which is intended for semaphores that outlive their owner
+///(e.g. placed in shared memory or referenced by handle from elsewhere), and must be closed via explicit `close()` instead.
+///
+///Forgetting to call `close()` leaks semaphore (e.g. kernel handle on Windows), which is safe.
+///Calling `close()` while semaphore is still used elsewhere is undefined behaviour, same as for unwrapped semaphore.
+///
+///## Usage
+///
+///```rust
+///let sem = semka::ManualClose::new(semka::Sem::new(1).unwrap());
+///assert!(sem.try_wait());
+///sem.signal();
+///unsafe {
+///    sem.close();
+///}
+///```
+#[repr(transparent)]
+pub struct ManualClose<T> {
+    inner: mem::ManuallyDrop<T>,
+}
+
+impl<T> ManualClose<T> {
+    #[inline(always)]
+    ///Wraps `inner`, which will not be dropped automatically.
+    pub const fn new(inner: T) -> Self {
+        Self {
+            inner: mem::ManuallyDrop::new(inner),
+        }
+    }
+
+    #[inline(always)]
+    ///Unwraps inner value, restoring its automatic deinitialization.
+    pub fn into_inner(self) -> T {
+        mem::ManuallyDrop::into_inner(self.inner)
+    }
+}
+
+impl<T> ops::Deref for ManualClose<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
@@ -0,0 +1,122 @@
+use core::time;
+use std::sync::{Mutex, MutexGuard, Condvar};
+
+use crate::Semaphore;
+
+struct State {
+    count: u32,
+    delay: time::Duration,
+    force_timeout: bool,
+}
+
+///In-memory semaphore for testing code generic over `Semaphore`.
+///
+///Deterministic implementation on top of `Mutex` and `Condvar`, which allows to inject artificial delays and
+///force timeouts, making it possible to test timeout paths without relying on OS scheduling.
+///
+///It is not intended for production use.
+pub struct MockSem {
+    state: Mutex<State>,
+    cond: Condvar,
+}
+
+impl MockSem {
+    ///Creates new instance with `init` as initial value.
+    pub fn new(init: u32) -> Self {
+        Self {
+            state: Mutex::new(State {
+                count: init,
+                delay: time::Duration::from_secs(0),
+                force_timeout: false,
+            }),
+            cond: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        //Mock is used in tests, so do not propagate poison from failed assertions
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(error) => error.into_inner(),
+        }
+    }
+
+    ///Returns current value.
+    pub fn value(&self) -> u32 {
+        self.lock().count
+    }
+
+    ///Sets delay to be performed before every attempt to decrement.
+    pub fn set_delay(&self, delay: time::Duration) {
+        self.lock().delay = delay;
+    }
+
+    ///Sets whether `wait_timeout` should always time out, without decrementing.
+    pub fn set_force_timeout(&self, force_timeout: bool) {
+        self.lock().force_timeout = force_timeout;
+    }
+
+    fn delay(&self) {
+        let delay = self.lock().delay;
+        if delay > time::Duration::from_secs(0) {
+            std::thread::sleep(delay);
+        }
+    }
+}
+
+impl Semaphore for MockSem {
+    fn wait(&self) {
+        self.delay();
+
+        let mut state = self.lock();
+        while state.count == 0 {
+            state = match self.cond.wait(state) {
+                Ok(state) => state,
+                Err(error) => error.into_inner(),
+            };
+        }
+        state.count -= 1;
+    }
+
+    fn try_wait(&self) -> bool {
+        self.delay();
+
+        let mut state = self.lock();
+        match state.count {
+            0 => false,
+            _ => {
+                state.count -= 1;
+                true
+            }
+        }
+    }
+
+    fn wait_timeout(&self, timeout: time::Duration) -> bool {
+        self.delay();
+
+        let state = self.lock();
+        if state.force_timeout {
+            return false;
+        }
+
+        let (mut state, _) = match self.cond.wait_timeout_while(state, timeout, |state| state.count == 0) {
+            Ok(result) => result,
+            Err(error) => error.into_inner(),
+        };
+
+        match state.count {
+            0 => false,
+            _ => {
+                state.count -= 1;
+                true
+            }
+        }
+    }
+
+    fn signal(&self) {
+        let mut state = self.lock();
+        state.count = state.count.saturating_add(1);
+        drop(state);
+        self.cond.notify_one();
+    }
+}
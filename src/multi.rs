@@ -0,0 +1,81 @@
+//Waiting on multiple semaphores for platforms without native support.
+
+use core::{ptr, time};
+
+use crate::Sem;
+
+//Time to block on single semaphore before re-checking the rest
+const POLL_INTERVAL: time::Duration = time::Duration::from_millis(1);
+
+impl Sem {
+    ///Awaits for any of `sems` to become signaled, decrementing it.
+    ///
+    ///Returns index of decremented semaphore.
+    ///
+    ///This platform has no native way to await multiple semaphores, hence it is emulated by polling:
+    ///all semaphores are checked in order, then single semaphore is awaited for short interval,
+    ///rotating between semaphores on each iteration.
+    ///As result semaphores with lower index are preferred when several are signaled at the same time,
+    ///and wake up may be delayed by up to 1ms.
+    ///
+    ///## Panics
+    ///
+    ///If `sems` is empty.
+    pub fn wait_any(sems: &[&Sem]) -> usize {
+        assert!(!sems.is_empty(), "wait_any() requires at least one semaphore");
+
+        let mut next = 0;
+        loop {
+            for (idx, sem) in sems.iter().enumerate() {
+                if sem.try_wait() {
+                    return idx;
+                }
+            }
+
+            if sems[next].wait_timeout(POLL_INTERVAL) {
+                return next;
+            }
+
+            next = (next + 1) % sems.len();
+        }
+    }
+
+    ///Awaits for all of `sems` to become signaled, decrementing each of them once.
+    ///
+    ///This platform has no native way to atomically acquire multiple semaphores, hence it is emulated:
+    ///single semaphore is awaited, then the rest are acquired without blocking.
+    ///If any of them is not signaled, all acquired permits are released and
+    ///the unavailable semaphore becomes the one to await on next attempt.
+    ///Permits are never held while blocking, avoiding deadlock of sequential acquisition,
+    ///but unlike Windows, other threads may observe and consume permits in between attempts.
+    ///
+    ///## Panics
+    ///
+    ///If `sems` is empty or contains the same semaphore multiple times,
+    ///as permit of one entry would always be taken from its alias, making it retry forever.
+    pub fn wait_all(sems: &[&Sem]) {
+        assert!(!sems.is_empty(), "wait_all() requires at least one semaphore");
+        for (idx, sem) in sems.iter().enumerate() {
+            assert!(!sems[..idx].iter().any(|other| ptr::eq(*other, *sem)), "wait_all() requires distinct semaphores");
+        }
+
+        let mut blocking = 0;
+        loop {
+            sems[blocking].wait();
+
+            let failed = sems.iter().enumerate().position(|(idx, sem)| idx != blocking && !sem.try_wait());
+            match failed {
+                None => break,
+                Some(failed) => {
+                    for (idx, sem) in sems[..failed].iter().enumerate() {
+                        if idx != blocking {
+                            sem.signal();
+                        }
+                    }
+                    sems[blocking].signal();
+                    blocking = failed;
+                }
+            }
+        }
+    }
+}
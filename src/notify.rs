@@ -0,0 +1,55 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Sem;
+
+///One-shot wakeup primitive, notifying single waiter.
+///
+///Similar to `tokio::sync::Notify`, but blocking and backed by OS semaphore.
+///
+///Notifications are not accumulated: there is at most one pending notification at a time.
+///If notification arrives before anyone waits, it is stored and consumed by next waiter,
+///which returns immediately. Any further notification, until it is consumed, is merged with pending one.
+///
+///Waiter may be woken by notification that arrived after it was already woken, but before it returned.
+///Hence waiter should inspect shared state after waking up, not before.
+pub struct Notify {
+    sem: Sem,
+    pending: AtomicBool,
+}
+
+impl Notify {
+    ///Creates new instance without pending notification.
+    pub fn new() -> Option<Self> {
+        Sem::new(0).map(|sem| Self {
+            sem,
+            pending: AtomicBool::new(false),
+        })
+    }
+
+    ///Notifies single waiter, or stores notification for next waiter if there is nobody waiting.
+    pub fn notify_one(&self) {
+        if !self.pending.swap(true, Ordering::AcqRel) {
+            self.sem.signal();
+        }
+    }
+
+    ///Awaits notification, returning immediately if there is pending one.
+    pub fn wait_for_notification(&self) {
+        self.sem.wait();
+        //Acquires writes of notifiers, whose notification was merged into the pending one
+        self.pending.swap(false, Ordering::AcqRel);
+    }
+
+    ///Consumes pending notification, if any.
+    ///
+    ///Returns `true` if there was pending notification.
+    pub fn try_wait_for_notification(&self) -> bool {
+        match self.sem.try_wait() {
+            true => {
+                self.pending.swap(false, Ordering::AcqRel);
+                true
+            },
+            false => false,
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//!Atomic counting semaphore, blocking via thread parking.
+//!
+//!Counter is plain `AtomicU32`, while waiters, after short spin, are put into FIFO queue and parked using `std::thread::park`.
+//!`signal` unparks waiter at the head of queue, hence it provides blocking without OS semaphore or futex,
+//!on any platform supported by `std`.
+//!
+//!Queue is intrusive, with nodes stored on waiters' stacks, so waiting never allocates.
+
+use core::cell::Cell;
+use core::ptr;
+use core::sync::atomic::{self, AtomicBool, AtomicU32, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::thread::{self, Thread};
+
+//Number of failed attempts before waiter is parked.
+const SPIN_LIMIT: u32 = 100;
+
+struct Waiter {
+    thread: Thread,
+    //Set by `signal` once waiter is removed from queue, after which node must not be accessed by it.
+    notified: AtomicBool,
+    next: Cell<*const Waiter>,
+}
+
+struct Queue {
+    head: *const Waiter,
+    tail: *const Waiter,
+}
+
+//Nodes are only accessed under lock
+unsafe impl Send for Queue {}
+
+impl Queue {
+    fn push(&mut self, waiter: &Waiter) {
+        match self.tail.is_null() {
+            true => self.head = waiter,
+            false => unsafe {
+                (*self.tail).next.set(waiter);
+            },
+        }
+        self.tail = waiter;
+    }
+
+    //Removes head of queue, waking it up.
+    fn notify_one(&mut self) -> bool {
+        if self.head.is_null() {
+            return false;
+        }
+
+        let waiter = unsafe {
+            &*self.head
+        };
+        self.head = waiter.next.get();
+        if self.head.is_null() {
+            self.tail = ptr::null();
+        }
+
+        //Waiter may return as soon as it observes `notified`, so handle must be cloned before
+        let thread = waiter.thread.clone();
+        waiter.notified.store(true, Ordering::Release);
+        thread.unpark();
+        true
+    }
+}
+
+///Counting semaphore based on atomic counter, blocking via thread parking.
+pub struct Sem {
+    count: AtomicU32,
+    //Number of threads in queue or about to enter it, used to skip locking queue in `signal`
+    waiters: AtomicU32,
+    queue: Mutex<Queue>,
+}
+
+impl Sem {
+    ///Maximum value of semaphore.
+    pub const MAX: u32 = u32::max_value();
+
+    ///Whether `wait_timeout` is supported.
+    ///
+    ///There is no `wait_timeout` for this semaphore.
+    pub const SUPPORTS_TIMEOUT: bool = false;
+
+    ///Granularity of timeout, which is zero as timeout is not supported.
+    pub const TIMEOUT_RESOLUTION: core::time::Duration = core::time::Duration::from_nanos(0);
+
+    ///Creates new instance with `init` as initial value.
+    pub const fn new(init: u32) -> Self {
+        Self {
+            count: AtomicU32::new(init),
+            waiters: AtomicU32::new(0),
+            queue: Mutex::new(Queue {
+                head: ptr::null(),
+                tail: ptr::null(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        //Queue is never left in inconsistent state, so poison can be ignored
+        match self.queue.lock() {
+            Ok(queue) => queue,
+            Err(error) => error.into_inner(),
+        }
+    }
+
+    #[inline]
+    ///Returns current value.
+    pub fn value(&self) -> u32 {
+        self.count.load(Ordering::Acquire)
+    }
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise, after short spin, parks until signal.
+    ///Spurious unparks are ignored, as waiter is only woken up once `signal` removes it from queue.
+    pub fn wait(&self) {
+        for _ in 0..SPIN_LIMIT {
+            if self.try_wait() {
+                return;
+            }
+            core::hint::spin_loop();
+        }
+
+        loop {
+            let waiter = Waiter {
+                thread: thread::current(),
+                notified: AtomicBool::new(false),
+                next: Cell::new(ptr::null()),
+            };
+
+            {
+                let mut queue = self.lock();
+                //Either `signal` observes us as waiter or we observe incremented count
+                self.waiters.fetch_add(1, Ordering::SeqCst);
+                atomic::fence(Ordering::SeqCst);
+                if self.try_wait() {
+                    self.waiters.fetch_sub(1, Ordering::Relaxed);
+                    return;
+                }
+                queue.push(&waiter);
+            }
+
+            while !waiter.notified.load(Ordering::Acquire) {
+                thread::park();
+            }
+
+            //Permit may be taken by another thread before us, in which case we queue again
+            if self.try_wait() {
+                return;
+            }
+        }
+    }
+
+    ///Attempts to decrement self, returning whether self was signaled or not.
+    ///
+    ///Returns `true` if self was signaled.
+    ///
+    ///Returns `false` otherwise.
+    pub fn try_wait(&self) -> bool {
+        let mut count = self.count.load(Ordering::Relaxed);
+        while count > 0 {
+            match self.count.compare_exchange_weak(count, count - 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => count = actual,
+            }
+        }
+
+        false
+    }
+
+    fn notify(&self, count: u32) {
+        if self.waiters.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+
+        let mut queue = self.lock();
+        for _ in 0..count {
+            if !queue.notify_one() {
+                break;
+            }
+            self.waiters.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    ///Increments self, unparking waiting thread, if any.
+    pub fn signal(&self) {
+        let count = self.count.fetch_add(1, Ordering::SeqCst);
+        strict_assert_ne!(count, Self::MAX, "Semaphore value overflow");
+
+        self.notify(1);
+    }
+
+    #[inline]
+    ///Increments self `count` times, unparking up to `count` waiting threads.
+    pub fn signal_many(&self, count: u32) {
+        let prev = self.count.fetch_add(count, Ordering::SeqCst);
+        strict_assert!(prev.checked_add(count).is_some(), "Semaphore value overflow");
+
+        self.notify(count);
+    }
+}
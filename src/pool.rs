@@ -0,0 +1,90 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::Sem;
+
+///Pool of permits, tracking number of permits checked out.
+///
+///Accounting is performed in-process, hence it is available regardless of whether backend can report its value.
+pub struct PermitPool {
+    sem: Sem,
+    capacity: u32,
+    outstanding: AtomicU32,
+}
+
+impl PermitPool {
+    ///Creates new instance with `capacity` permits.
+    pub fn new(capacity: u32) -> Option<Self> {
+        Sem::new(capacity).map(|sem| Self {
+            sem,
+            capacity,
+            outstanding: AtomicU32::new(0),
+        })
+    }
+
+    #[inline(always)]
+    ///Returns underlying semaphore.
+    pub fn sem(&self) -> &Sem {
+        &self.sem
+    }
+
+    #[inline(always)]
+    ///Returns total number of permits.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    #[inline]
+    ///Returns number of permits currently checked out.
+    pub fn outstanding(&self) -> u32 {
+        self.outstanding.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    ///Returns number of permits that are not checked out.
+    pub fn available(&self) -> u32 {
+        self.capacity.saturating_sub(self.outstanding())
+    }
+
+    #[inline(always)]
+    fn checkout(&self) -> PoolPermit<'_> {
+        self.outstanding.fetch_add(1, Ordering::AcqRel);
+        PoolPermit {
+            pool: self
+        }
+    }
+
+    ///Checks out permit, awaiting until it is available.
+    pub fn acquire(&self) -> PoolPermit<'_> {
+        self.sem.wait();
+        self.checkout()
+    }
+
+    ///Checks out permit if it is available, otherwise returns `None`.
+    pub fn try_acquire(&self) -> Option<PoolPermit<'_>> {
+        match self.sem.try_wait() {
+            true => Some(self.checkout()),
+            false => None,
+        }
+    }
+}
+
+///Permit checked out of [PermitPool](struct.PermitPool.html), which is returned on drop.
+pub struct PoolPermit<'a> {
+    pool: &'a PermitPool,
+}
+
+impl<'a> PoolPermit<'a> {
+    #[inline(always)]
+    ///Returns pool this permit belongs to.
+    pub fn pool(&self) -> &'a PermitPool {
+        self.pool
+    }
+}
+
+impl Drop for PoolPermit<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.pool.outstanding.fetch_sub(1, Ordering::AcqRel);
+        self.pool.sem.signal();
+    }
+}
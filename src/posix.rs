@@ -1,22 +1,106 @@
-use core::mem;
+use core::{mem, ptr};
 use core::cell::UnsafeCell;
 use core::sync::atomic::{AtomicU8, Ordering};
 
 use error_code::ErrorCode;
 
-use crate::unlikely;
+use crate::{unlikely, SemError, TimeoutPolicy};
+use crate::ceiling::Ceiling;
+use crate::spin::{Backoff, SpinStrategy};
 
 const UNINIT: u8 = 0;
 const INITING: u8 = 0b01;
 const INITED: u8 = 0b10;
 
+//Interval between checks when polling semaphore's value, in nanoseconds.
+const POLL_INTERVAL: libc::c_long = 1_000_000;
+
+//Returns absolute deadline after `duration`.
+//
+//If deadline cannot be represented by `timespec`, returns saturated deadline as error.
+fn deadline(duration: core::time::Duration) -> Result<libc::timespec, libc::timespec> {
+    use core::convert::TryFrom;
+
+    let mut timeout = mem::MaybeUninit::uninit();
+    if unsafe { libc::clock_gettime(libc::CLOCK_REALTIME, timeout.as_mut_ptr()) } == -1 {
+        panic!("Failed to get current time");
+    }
+
+    let mut timeout = unsafe {
+        timeout.assume_init()
+    };
+
+    let secs = libc::time_t::try_from(duration.as_secs()).ok().and_then(|secs| timeout.tv_sec.checked_add(secs));
+    let mut overflow = secs.is_none();
+    timeout.tv_sec = secs.unwrap_or(libc::time_t::max_value());
+    //Both are below 1s, so sum cannot overflow and carries at most 1s
+    timeout.tv_nsec = timeout.tv_nsec + duration.subsec_nanos() as libc::c_long;
+    if timeout.tv_nsec > 999_999_999 {
+        timeout.tv_nsec -= 1_000_000_000;
+        match timeout.tv_sec.checked_add(1) {
+            Some(secs) => timeout.tv_sec = secs,
+            None => overflow = true,
+        }
+    }
+
+    match overflow {
+        false => Ok(timeout),
+        true => Err(timeout),
+    }
+}
+
+fn poll_sleep() {
+    let interval = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: POLL_INTERVAL,
+    };
+
+    unsafe {
+        libc::nanosleep(&interval, ptr::null_mut());
+    }
+}
+
 ///POSIX implementation of Semaphore
+///
+///Using uninitialized semaphore is undefined behaviour, hence it panics in debug builds or with `strict` feature.
 pub struct Sem {
     handle: UnsafeCell<mem::MaybeUninit<libc::sem_t>>,
     state: AtomicU8,
+    ceiling: Ceiling,
 }
 
+//`sem_init` accepts `unsigned int`, which must hold any value up to `Sem::MAX` without truncation
+const _: () = assert!(mem::size_of::<libc::c_uint>() >= mem::size_of::<u32>());
+
+//sem_t must be stored with its own alignment
+const _: () = assert!(mem::align_of::<Sem>() >= mem::align_of::<libc::sem_t>());
+const _: () = assert!(mem::size_of::<Sem>() > mem::size_of::<libc::sem_t>());
+
+//Size of sem_t as defined by C library headers.
+//If libc's definition disagrees, semaphore would be stored in insufficient memory, so refuse to compile.
+//glibc and musl: `4 * sizeof(long)` bytes.
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+const _: () = assert!(mem::size_of::<libc::sem_t>() == 4 * mem::size_of::<libc::c_long>(), "libc::sem_t size doesn't match glibc/musl definition");
+//bionic: `unsigned int` with 3 reserved `int` on LP64.
+#[cfg(all(target_os = "android", target_pointer_width = "64"))]
+const _: () = assert!(mem::size_of::<libc::sem_t>() == 4 * mem::size_of::<libc::c_uint>(), "libc::sem_t size doesn't match bionic definition");
+#[cfg(all(target_os = "android", not(target_pointer_width = "64")))]
+const _: () = assert!(mem::size_of::<libc::sem_t>() == mem::size_of::<libc::c_uint>(), "libc::sem_t size doesn't match bionic definition");
+
 impl Sem {
+    ///Maximum value of semaphore.
+    ///
+    ///Corresponds to `SEM_VALUE_MAX`, which is `INT_MAX` on all supported platforms.
+    pub const MAX: u32 = i32::max_value() as u32;
+
+    ///Whether `wait_timeout` is supported.
+    pub const SUPPORTS_TIMEOUT: bool = true;
+
+    ///Granularity of timeout accepted by `wait_timeout`.
+    ///
+    ///`sem_timedwait` accepts `timespec`, hence it is nanosecond, but actual precision is subject to OS scheduler.
+    pub const TIMEOUT_RESOLUTION: core::time::Duration = core::time::Duration::from_nanos(1);
+
     ///Creates new uninit instance.
     ///
     ///It is UB to use it until `init` is called.
@@ -24,9 +108,16 @@ impl Sem {
         Self {
             handle: UnsafeCell::new(mem::MaybeUninit::uninit()),
             state: AtomicU8::new(UNINIT),
+            ceiling: Ceiling::untracked(),
         }
     }
 
+    #[inline(always)]
+    fn as_ptr(&self) -> *mut libc::sem_t {
+        //MaybeUninit is transparent wrapper, hence pointer can be used as is
+        self.handle.get() as *mut libc::sem_t
+    }
+
     #[inline(always)]
     ///Returns whether semaphore is successfully initialized
     pub fn is_init(&self) -> bool {
@@ -36,9 +127,19 @@ impl Sem {
     #[cold]
     #[inline(never)]
     fn await_init(&self) {
-        //Wait for initialization to finish
+        //Wait for initialization to finish.
+        //It is normally quick, so spin with exponential backoff first,
+        //then give up CPU to let initializing thread make progress
+        let mut iteration = 0;
         while self.state.load(Ordering::Acquire) == INITING {
-            core::hint::spin_loop();
+            if iteration <= Backoff::LIMIT {
+                Backoff.spin(iteration);
+                iteration += 1;
+            } else {
+                unsafe {
+                    libc::sched_yield();
+                }
+            }
         }
     }
 
@@ -48,21 +149,44 @@ impl Sem {
     ///Returns `true` on success.
     ///
     ///Returns `false` if semaphore is already initialized or initialization failed.
+    ///
+    ///Initialization fails if `init` is greater than maximum, which is `Sem::MAX` unless semaphore is created with `new_with_max`.
     pub fn init(&self, init: u32) -> bool {
+        self.init_checked(init).is_ok()
+    }
+
+    ///Initializes semaphore with provided `init` as initial value, reporting reason of failure.
+    ///
+    ///Valid range of `init` is from zero to maximum, which is `Sem::MAX` (`SEM_VALUE_MAX`) unless semaphore is created with `new_with_max`.
+    ///Value is checked before calling `sem_init`, hence it is never truncated or rejected by it with generic `EINVAL`.
+    ///
+    ///Returns `Err(SemError::Overflow)` if `init` is out of range.
+    ///
+    ///Returns `Err(SemError::AlreadyInitialized)` if semaphore is already initialized.
+    ///
+    ///Returns `Err(SemError::Os)` if `sem_init` fails.
+    pub fn init_checked(&self, init: u32) -> Result<(), SemError> {
+        if init > self.max() {
+            return unlikely(Err(SemError::Overflow));
+        }
+
         if let Ok(UNINIT) = self.state.compare_exchange(UNINIT, INITING, Ordering::SeqCst, Ordering::Acquire) {
             let res = unsafe {
-                libc::sem_init(self.handle.get() as _, 0, init as _)
+                libc::sem_init(self.as_ptr(), 0, init as libc::c_uint)
             };
 
             let res = match res {
                 0 => {
+                    self.ceiling.init(init);
                     self.state.store(INITED, Ordering::Release);
-                    true
+                    Ok(())
                 },
                 _ => {
-                    //TODO: assert against?
+                    let error = SemError::from_errno(ErrorCode::last_posix().raw_code());
+                    //Failed sem_init may leave garbage in handle, but it is never read:
+                    //handle is only used once INITED, and sem_init overwrites it entirely, without reading prior content.
                     self.state.store(UNINIT, Ordering::Release);
-                    false
+                    Err(error)
                 },
             };
 
@@ -74,7 +198,7 @@ impl Sem {
                 self.await_init();
             }
 
-            false
+            Err(SemError::AlreadyInitialized)
         }
     }
 
@@ -91,25 +215,160 @@ impl Sem {
         }
     }
 
+    ///Creates new instance, initializing it with `init`, which value never exceeds `max`.
+    ///
+    ///POSIX semaphores do not support maximum value, hence it is emulated using counter of permits,
+    ///which is updated on every increment and successful decrement, adding overhead of atomic operation.
+    ///
+    ///Returns `None` if `max` is zero, below `init` or above `Sem::MAX`, or initialization fails.
+    pub fn new_with_max(init: u32, max: u32) -> Option<Self> {
+        if max == 0 || init > max || max > Self::MAX {
+            return unlikely(None);
+        }
+
+        let result = Self {
+            handle: UnsafeCell::new(mem::MaybeUninit::uninit()),
+            state: AtomicU8::new(UNINIT),
+            ceiling: match max {
+                Self::MAX => Ceiling::untracked(),
+                max => Ceiling::new(max),
+            },
+        };
+
+        if result.init(init) {
+            Some(result)
+        } else {
+            unlikely(None)
+        }
+    }
+
+    #[inline]
+    ///Returns maximum value of semaphore.
+    pub fn max(&self) -> u32 {
+        self.ceiling.max().unwrap_or(Self::MAX)
+    }
+
+    #[cfg(feature = "counting")]
+    #[inline]
+    //Number of permits that can be added without exceeding maximum
+    pub(crate) fn headroom(&self) -> Option<u32> {
+        Some(self.max().saturating_sub(self.value()))
+    }
+
+    #[inline(always)]
+    ///Always returns `None`.
+    ///
+    ///Semaphore is unnamed `sem_t` owned by this instance, so there is no other handle that can refer to it.
+    ///Share it by reference (e.g. `Arc<Sem>`) instead.
+    pub fn duplicate(&self) -> Option<Self> {
+        None
+    }
+
     ///Decrements self, returning immediately if it was signaled.
     ///
     ///Otherwise awaits for signal.
     pub fn wait(&self) {
+        strict_assert!(self.is_init(), "wait() on uninitialized Sem");
+
         loop {
             let res = unsafe {
-                libc::sem_wait(mem::transmute(self.handle.get()))
+                libc::sem_wait(self.as_ptr())
             };
 
             if res == -1 {
                 let errno = ErrorCode::last_posix();
-                debug_assert_eq!(errno.raw_code(), libc::EINTR, "Unexpected error");
+                strict_assert_eq!(errno.raw_code(), libc::EINTR, "Unexpected error");
                 continue;
             }
 
+            self.ceiling.release();
             break
         }
     }
 
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal.
+    ///
+    ///Unlike `wait`, blocks all signals (via `pthread_sigmask`) for duration of wait, so that
+    ///it cannot be interrupted, restoring signal mask afterwards.
+    ///Signals sent to this thread are only delivered once wait is finished,
+    ///while signals sent to process are delivered to other threads that do not block them.
+    ///Hence it should not be used if application relies on handling signals in waiting thread.
+    pub fn wait_masked(&self) {
+        let mut all = mem::MaybeUninit::uninit();
+        let mut prev = mem::MaybeUninit::uninit();
+
+        unsafe {
+            libc::sigfillset(all.as_mut_ptr());
+            let res = libc::pthread_sigmask(libc::SIG_BLOCK, all.as_ptr(), prev.as_mut_ptr());
+            strict_assert_eq!(res, 0, "pthread_sigmask() failed");
+        }
+
+        //Signals that cannot be blocked still need to be handled
+        self.wait();
+
+        unsafe {
+            libc::pthread_sigmask(libc::SIG_SETMASK, prev.as_ptr(), ptr::null_mut());
+        }
+    }
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal.
+    ///
+    ///Unlike `wait`, returns error on any failure other than interruption by signal.
+    pub fn wait_checked(&self) -> Result<(), SemError> {
+        if !self.is_init() {
+            return Err(SemError::Uninitialized);
+        }
+
+        loop {
+            let res = unsafe {
+                libc::sem_wait(self.as_ptr())
+            };
+
+            if res == -1 {
+                let errno = ErrorCode::last_posix().raw_code();
+                if errno == libc::EINTR {
+                    continue;
+                }
+
+                break Err(SemError::from_errno(errno));
+            }
+
+            self.ceiling.release();
+            break Ok(())
+        }
+    }
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal.
+    ///
+    ///Returns number of times wait was interrupted by signal (`EINTR`) and retried.
+    ///This is diagnostic information only and should be treated as best-effort.
+    pub fn wait_counting_interrupts(&self) -> u32 {
+        strict_assert!(self.is_init(), "wait_counting_interrupts() on uninitialized Sem");
+
+        let mut interrupts = 0u32;
+        loop {
+            let res = unsafe {
+                libc::sem_wait(self.as_ptr())
+            };
+
+            if res == -1 {
+                let errno = ErrorCode::last_posix();
+                strict_assert_eq!(errno.raw_code(), libc::EINTR, "Unexpected error");
+                interrupts = interrupts.saturating_add(1);
+                continue;
+            }
+
+            self.ceiling.release();
+            break interrupts
+        }
+    }
+
     #[inline]
     ///Attempts to decrement self, returning whether self was signaled or not.
     ///
@@ -117,9 +376,11 @@ impl Sem {
     ///
     ///Returns `false` otherwise.
     pub fn try_wait(&self) -> bool {
+        strict_assert!(self.is_init(), "try_wait() on uninitialized Sem");
+
         loop {
             let res = unsafe {
-                libc::sem_trywait(mem::transmute(self.handle.get()))
+                libc::sem_trywait(self.as_ptr())
             };
 
             if res == -1 {
@@ -128,38 +389,107 @@ impl Sem {
                     break false;
                 }
 
-                debug_assert_eq!(errno, libc::EINTR, "Unexpected error");
+                strict_assert_eq!(errno, libc::EINTR, "Unexpected error");
                 continue;
             }
 
+            self.ceiling.release();
             break true
         }
     }
 
+    ///Attempts to decrement self, returning whether self was signaled or not.
+    ///
+    ///Unlike `try_wait`, distinguishes empty semaphore from failure.
+    ///
+    ///Returns `Ok(true)` if self was signaled.
+    ///
+    ///Returns `Ok(false)` if self was not signaled (`EAGAIN`).
+    ///
+    ///Returns `Err` on any other error (e.g. `EINVAL` for invalid semaphore).
+    pub fn try_wait_checked(&self) -> Result<bool, SemError> {
+        if !self.is_init() {
+            return Err(SemError::Uninitialized);
+        }
+
+        loop {
+            let res = unsafe {
+                libc::sem_trywait(self.as_ptr())
+            };
+
+            if res == -1 {
+                let errno = ErrorCode::last_posix().raw_code();
+                if errno == libc::EAGAIN || errno == libc::EWOULDBLOCK {
+                    break Ok(false);
+                } else if errno == libc::EINTR {
+                    continue;
+                }
+
+                break Err(SemError::from_errno(errno));
+            }
+
+            self.ceiling.release();
+            break Ok(true)
+        }
+    }
+
     ///Attempts to decrement self within provided time, returning whether self was signaled or not.
     ///
+    ///Timeout is converted into absolute deadline with nanosecond precision.
+    ///Effective resolution depends on OS timers: on Linux it is normally tens of microseconds (less with PREEMPT_RT),
+    ///while other systems may round it up to scheduler tick.
+    ///
     ///Returns `true` if self was signaled within specified timeout
     ///
     ///Returns `false` otherwise
+    ///
+    ///Zero timeout is equivalent to `try_wait`, avoiding clock access.
     pub fn wait_timeout(&self, duration: core::time::Duration) -> bool {
-        let mut timeout = mem::MaybeUninit::uninit();
-        if unsafe { libc::clock_gettime(libc::CLOCK_REALTIME, timeout.as_mut_ptr()) } == -1 {
-            panic!("Failed to get current time");
+        if duration == core::time::Duration::from_secs(0) {
+            return self.try_wait();
         }
 
-        let mut timeout = unsafe {
-            timeout.assume_init()
+        let deadline = match deadline(duration) {
+            Ok(deadline) => deadline,
+            Err(deadline) => deadline,
         };
-        timeout.tv_sec = timeout.tv_sec.saturating_add(duration.as_secs() as _);
-        timeout.tv_nsec = timeout.tv_nsec.saturating_add(duration.subsec_nanos() as _);
-        if timeout.tv_nsec > 999999999 {
-            timeout.tv_nsec = 0;
-            timeout.tv_sec = timeout.tv_sec.saturating_add(1);
+
+        self.wait_deadline(&deadline)
+    }
+
+    ///Attempts to decrement self within provided time, returning whether self was signaled or not.
+    ///
+    ///Timeout is converted into absolute deadline, which must fit `timespec`, otherwise `policy` is applied.
+    ///
+    ///Returns `Ok(true)` if self was signaled within specified timeout
+    ///
+    ///Returns `Ok(false)` otherwise
+    ///
+    ///Returns `Err(SemError::TimeoutOverflow)` if timeout overflows and `policy` is `TimeoutPolicy::Error`
+    pub fn wait_timeout_with_policy(&self, duration: core::time::Duration, policy: TimeoutPolicy) -> Result<bool, SemError> {
+        if duration == core::time::Duration::from_secs(0) {
+            return Ok(self.try_wait());
         }
 
+        match deadline(duration) {
+            Ok(deadline) => Ok(self.wait_deadline(&deadline)),
+            Err(deadline) => match policy {
+                TimeoutPolicy::Saturate => Ok(self.wait_deadline(&deadline)),
+                TimeoutPolicy::Error => Err(SemError::TimeoutOverflow),
+                TimeoutPolicy::Infinite => {
+                    self.wait();
+                    Ok(true)
+                },
+            },
+        }
+    }
+
+    fn wait_deadline(&self, deadline: &libc::timespec) -> bool {
+        strict_assert!(self.is_init(), "wait_timeout() on uninitialized Sem");
+
         loop {
             let res = unsafe {
-                libc::sem_timedwait(mem::transmute(self.handle.get()), &timeout)
+                libc::sem_timedwait(self.as_ptr(), deadline)
             };
 
             if res == -1 {
@@ -174,26 +504,180 @@ impl Sem {
                 continue;
             }
 
+            self.ceiling.release();
             break true
         }
     }
 
     ///Increments self, waking any awaiting thread as result.
     pub fn signal(&self) {
+        strict_assert!(self.is_init(), "signal() on uninitialized Sem");
+
+        let reserved = self.ceiling.reserve(1);
+        strict_assert!(reserved, "Semaphore value would exceed its maximum");
+        if !reserved {
+            return;
+        }
+
+        let res = unsafe {
+            libc::sem_post(self.as_ptr())
+        };
+        if res != 0 {
+            self.ceiling.unreserve(1);
+        }
+        strict_assert_eq!(res, 0);
+    }
+
+    #[inline]
+    ///Increments self, waking any awaiting thread as result.
+    ///
+    ///`sem_post` is async-signal-safe and this method performs no other work (no assertions or panics),
+    ///except for atomic update of emulated maximum, if any,
+    ///so unlike `signal` it can be used within signal handler to wake up awaiting thread.
+    pub fn signal_from_handler(&self) {
+        if self.ceiling.reserve(1) {
+            let res = unsafe {
+                libc::sem_post(self.as_ptr())
+            };
+            if res != 0 {
+                self.ceiling.unreserve(1);
+            }
+        }
+    }
+
+    ///Increments self, waking any awaiting thread as result.
+    ///
+    ///Unlike `signal`, reports failure to increment, in which case permit is not added.
+    ///
+    ///Returns `SemError::Overflow` if semaphore is already at its maximum.
+    pub fn signal_checked(&self) -> Result<(), SemError> {
+        if !self.is_init() {
+            return Err(SemError::Uninitialized);
+        } else if !self.ceiling.reserve(1) {
+            return Err(SemError::Overflow);
+        }
+
+        let res = unsafe {
+            libc::sem_post(self.as_ptr())
+        };
+
+        match res {
+            0 => Ok(()),
+            _ => {
+                self.ceiling.unreserve(1);
+                Err(SemError::from_errno(ErrorCode::last_posix().raw_code()))
+            },
+        }
+    }
+
+    ///Increments self `count` times, waking awaiting threads as result.
+    ///
+    ///POSIX has no batched post (`sem_post_multiple` is extension absent on supported platforms),
+    ///hence it performs `sem_post` per each increment.
+    pub fn signal_many(&self, count: u32) {
+        strict_assert!(self.value().saturating_add(count) <= self.max(), "Semaphore value would exceed its maximum");
+
+        for _ in 0..count {
+            self.signal();
+        }
+    }
+
+    #[inline]
+    ///Returns current value of semaphore.
+    ///
+    ///Value may be changed by other threads at any moment, hence it should be treated as a hint only.
+    ///
+    ///Only available on POSIX, as other platforms provide no way to query semaphore's value.
+    pub fn value(&self) -> u32 {
+        strict_assert!(self.is_init(), "value() on uninitialized Sem");
+
+        let mut value = 0;
+        let res = unsafe {
+            libc::sem_getvalue(self.as_ptr(), &mut value)
+        };
+        strict_assert_eq!(res, 0);
+
+        //Some implementations report number of waiters as negative value
+        if value < 0 {
+            0
+        } else {
+            value as u32
+        }
+    }
+
+    ///Returns number of threads waiting on semaphore, if platform reports it.
+    ///
+    ///POSIX allows `sem_getvalue` to report number of waiters as negative value, but it is not universal:
+    ///glibc and bionic always report zero instead, hence on Linux and Android it returns `None`.
+    ///On other platforms returns `Some(0)` when value is not negative, which may mean that there are no waiters,
+    ///or that platform doesn't report them.
+    ///
+    ///Number of waiters may be changed by other threads at any moment, hence it should be treated as a hint only.
+    pub fn waiters(&self) -> Option<u32> {
+        strict_assert!(self.is_init(), "waiters() on uninitialized Sem");
+
+        if cfg!(any(target_os = "linux", target_os = "android")) {
+            return None;
+        }
+
+        let mut value = 0;
         let res = unsafe {
-            libc::sem_post(mem::transmute(self.handle.get()))
+            libc::sem_getvalue(self.as_ptr(), &mut value)
         };
-        debug_assert_eq!(res, 0);
+        strict_assert_eq!(res, 0);
+
+        match value < 0 {
+            true => Some(value.unsigned_abs()),
+            false => Some(0),
+        }
     }
 
+    ///Awaits for semaphore value to become zero, without modifying it.
+    ///
+    ///Useful to wait until all available permits are consumed.
+    ///
+    ///There is no way to block until semaphore is empty, so value is polled every 1ms.
+    ///
+    ///Only available on POSIX, as other platforms provide no way to query semaphore's value.
+    pub fn wait_until_empty(&self) {
+        while self.value() != 0 {
+            poll_sleep();
+        }
+    }
+
+    ///Awaits for semaphore value to become at least `count`, without modifying it.
+    ///
+    ///Unlike `wait`, no permits are consumed, so other threads may acquire them right after it returns,
+    ///hence caller cannot assume that `count` permits are still available when it acts upon it.
+    ///
+    ///There is no way to block until value reaches `count`, so value is polled every 1ms.
+    ///
+    ///Only available on POSIX, as other platforms provide no way to query semaphore's value.
+    pub fn wait_for_count(&self, count: u32) {
+        while self.value() < count {
+            poll_sleep();
+        }
+    }
+
+    #[inline]
+    ///Consumes self without performing deinitialization.
+    ///
+    ///`sem_destroy` is never called for this semaphore.
+    ///Cleanup becomes responsibility of whoever owns underlying semaphore, if anyone.
+    ///As `Drop` is not run, there is no risk of closing semaphore twice.
+    pub fn leak(self) {
+        core::mem::forget(self);
+    }
 
     ///Performs deinitialization.
     ///
     ///Using `Sem` after `close` is undefined behaviour, unless `init` is called
+    ///
+    ///Semaphore returns to uninitialized state, after which `is_init` returns `false` and `init` can be called again.
+    ///Closing uninitialized semaphore does nothing, so `close` can be safely called multiple times.
     pub unsafe fn close(&self) {
-        let handle = self.handle.get();
         if let Ok(INITED) = self.state.compare_exchange(INITED, UNINIT, Ordering::SeqCst, Ordering::Acquire) {
-            libc::sem_destroy(mem::transmute(handle));
+            libc::sem_destroy(self.as_ptr());
         }
     }
 }
@@ -0,0 +1,139 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{Sem, BinarySem};
+
+///Readers-writer lock built on semaphores.
+///
+///Readers share counting semaphore with `max_readers` permits, each reader holding one,
+///while writer acquires all of them, excluding both readers and other writers.
+///
+///Writers are serialized by binary semaphore (turnstile), which readers also pass through before acquiring permit.
+///Hence lock is writer-preferring: once writer arrives, new readers are blocked until it is done,
+///so writers cannot be starved by continuous stream of readers, but readers can be starved by continuous stream of writers.
+///
+///Writer acquires permits one by one and releases them in batch, hence cost of write lock is O(`max_readers`)
+///semaphore operations, which is why `max_readers` is limited by `RwSem::MAX_READERS`.
+pub struct RwSem {
+    readers: Sem,
+    turnstile: BinarySem,
+    //Set while writer holds turnstile, to distinguish it from readers passing through
+    writer: AtomicBool,
+    max_readers: u32,
+}
+
+impl RwSem {
+    ///Maximum number of concurrent readers, bounding cost of write lock.
+    pub const MAX_READERS: u32 = 1024;
+
+    ///Creates new instance, allowing up to `max_readers` concurrent readers.
+    ///
+    ///Returns `None` if `max_readers` is zero or above `RwSem::MAX_READERS`, or creation of semaphores fails.
+    pub fn new(max_readers: u32) -> Option<Self> {
+        if max_readers == 0 || max_readers > Self::MAX_READERS {
+            return None;
+        }
+
+        Some(Self {
+            readers: Sem::new(max_readers)?,
+            turnstile: BinarySem::new()?,
+            writer: AtomicBool::new(false),
+            max_readers,
+        })
+    }
+
+    #[inline(always)]
+    ///Returns maximum number of concurrent readers.
+    pub fn max_readers(&self) -> u32 {
+        self.max_readers
+    }
+
+    ///Acquires shared access, awaiting until there is no writer.
+    pub fn read(&self) -> RwReadGuard<'_> {
+        drop(self.turnstile.lock());
+        self.readers.wait();
+        RwReadGuard {
+            lock: self,
+        }
+    }
+
+    ///Attempts to acquire shared access, returning `None` if it is not available immediately.
+    ///
+    ///Fails only if writer is waiting for or holding exclusive access, or all `max_readers` permits are taken.
+    pub fn try_read(&self) -> Option<RwReadGuard<'_>> {
+        let turn = loop {
+            match self.turnstile.try_lock() {
+                Some(turn) => break turn,
+                None if self.writer.load(Ordering::Relaxed) => return None,
+                //Turnstile is held only briefly by readers passing through it
+                None => core::hint::spin_loop(),
+            }
+        };
+        drop(turn);
+        match self.readers.try_wait() {
+            true => Some(RwReadGuard {
+                lock: self,
+            }),
+            false => None,
+        }
+    }
+
+    ///Acquires exclusive access, awaiting until all readers and writers are done.
+    ///
+    ///Performs `max_readers` waits on underlying semaphore.
+    pub fn write(&self) -> RwWriteGuard<'_> {
+        let _turn = self.turnstile.lock();
+        self.writer.store(true, Ordering::Relaxed);
+        for _ in 0..self.max_readers {
+            self.readers.wait();
+        }
+        self.writer.store(false, Ordering::Relaxed);
+
+        RwWriteGuard {
+            lock: self,
+        }
+    }
+
+    ///Attempts to acquire exclusive access, returning `None` if it is not available immediately.
+    ///
+    ///Performs up to `max_readers` attempts to decrement underlying semaphore.
+    pub fn try_write(&self) -> Option<RwWriteGuard<'_>> {
+        let _turn = self.turnstile.try_lock()?;
+        self.writer.store(true, Ordering::Relaxed);
+        for acquired in 0..self.max_readers {
+            if !self.readers.try_wait() {
+                self.readers.signal_many(acquired);
+                self.writer.store(false, Ordering::Relaxed);
+                return None;
+            }
+        }
+        self.writer.store(false, Ordering::Relaxed);
+
+        Some(RwWriteGuard {
+            lock: self,
+        })
+    }
+}
+
+///Shared access to [RwSem](struct.RwSem.html), released on drop.
+pub struct RwReadGuard<'a> {
+    lock: &'a RwSem,
+}
+
+impl Drop for RwReadGuard<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.lock.readers.signal();
+    }
+}
+
+///Exclusive access to [RwSem](struct.RwSem.html), released on drop.
+pub struct RwWriteGuard<'a> {
+    lock: &'a RwSem,
+}
+
+impl Drop for RwWriteGuard<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.lock.readers.signal_many(self.lock.max_readers);
+    }
+}
@@ -0,0 +1,65 @@
+use core::time;
+
+use crate::Sem;
+
+///Common interface of counting semaphores.
+///
+///Allows to write code generic over semaphore implementation.
+pub trait Semaphore {
+    ///Whether `wait_timeout` is supported.
+    ///
+    ///Generic code can use it to avoid relying on timeout, where implementation cannot provide it.
+    const SUPPORTS_TIMEOUT: bool = true;
+
+    ///Granularity of timeout accepted by `wait_timeout`.
+    ///
+    ///It is minimal timeout step, not guaranteed precision, which is subject to OS scheduler.
+    const TIMEOUT_RESOLUTION: time::Duration = time::Duration::from_millis(1);
+
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal.
+    fn wait(&self);
+
+    ///Attempts to decrement self, returning whether self was signaled or not.
+    ///
+    ///Returns `true` if self was signaled.
+    ///
+    ///Returns `false` otherwise.
+    fn try_wait(&self) -> bool;
+
+    ///Attempts to decrement self within provided time, returning whether self was signaled or not.
+    ///
+    ///Returns `true` if self was signaled within specified timeout
+    ///
+    ///Returns `false` otherwise
+    fn wait_timeout(&self, timeout: time::Duration) -> bool;
+
+    ///Increments self, waking any awaiting thread as result.
+    fn signal(&self);
+}
+
+impl Semaphore for Sem {
+    const SUPPORTS_TIMEOUT: bool = Sem::SUPPORTS_TIMEOUT;
+    const TIMEOUT_RESOLUTION: time::Duration = Sem::TIMEOUT_RESOLUTION;
+
+    #[inline(always)]
+    fn wait(&self) {
+        Sem::wait(self)
+    }
+
+    #[inline(always)]
+    fn try_wait(&self) -> bool {
+        Sem::try_wait(self)
+    }
+
+    #[inline(always)]
+    fn wait_timeout(&self, timeout: time::Duration) -> bool {
+        Sem::wait_timeout(self, timeout)
+    }
+
+    #[inline(always)]
+    fn signal(&self) {
+        Sem::signal(self)
+    }
+}
@@ -0,0 +1,53 @@
+//!Strategies of busy-wait.
+//!
+//!Atomic semaphores, which have no way to sleep, busy-wait until signal.
+//![SpinStrategy](trait.SpinStrategy.html) makes it tunable: pure spin minimizes latency,
+//!while backoff or yielding reduces power consumption and contention, at the cost of reacting later.
+
+///Strategy of busy-wait between attempts to acquire semaphore.
+pub trait SpinStrategy {
+    ///Performs single pause after `iteration` failed attempts, starting from zero.
+    fn spin(&self, iteration: u32);
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+///Pause via single `core::hint::spin_loop()` on every iteration.
+pub struct PureSpin;
+
+impl SpinStrategy for PureSpin {
+    #[inline(always)]
+    fn spin(&self, _: u32) {
+        core::hint::spin_loop();
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+///Exponential backoff, doubling number of `core::hint::spin_loop()` on every iteration up to `2^6`.
+pub struct Backoff;
+
+impl Backoff {
+    //Iteration, starting from which number of spins no longer grows
+    pub(crate) const LIMIT: u32 = 6;
+}
+
+impl SpinStrategy for Backoff {
+    #[inline]
+    fn spin(&self, iteration: u32) {
+        for _ in 0..(1u32 << iteration.min(Self::LIMIT)) {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+///Gives up CPU via `std::thread::yield_now()` on every iteration.
+pub struct Yield;
+
+#[cfg(feature = "std")]
+impl SpinStrategy for Yield {
+    #[inline(always)]
+    fn spin(&self, _: u32) {
+        std::thread::yield_now();
+    }
+}
@@ -0,0 +1,49 @@
+//Waits measuring elapsed time, which requires std's clock.
+
+use core::time;
+use std::time::Instant;
+
+use crate::{Sem, SemGuard};
+
+impl Sem {
+    ///Attempts to decrement self within provided time, returning unused portion of timeout on success.
+    ///
+    ///Returns `Some(remaining)` if self was signaled within specified timeout, allowing to carry remaining time to the next
+    ///operation with the same deadline.
+    ///
+    ///Returns `None` otherwise
+    pub fn wait_timeout_remaining(&self, timeout: time::Duration) -> Option<time::Duration> {
+        let start = Instant::now();
+        match self.wait_timeout(timeout) {
+            true => Some(timeout.checked_sub(start.elapsed()).unwrap_or(time::Duration::from_secs(0))),
+            false => None,
+        }
+    }
+
+    ///Attempts to decrement self within provided time, returning guard along with unused portion of timeout.
+    ///
+    ///Combines `wait_timeout_remaining` with guard, which increments self on drop.
+    ///
+    ///Returns `Err(())` if self was not signaled within specified timeout.
+    pub fn try_acquire_for_remaining(&self, timeout: time::Duration) -> Result<(SemGuard<'_>, time::Duration), ()> {
+        match self.wait_timeout_remaining(timeout) {
+            Some(remaining) => Ok((SemGuard::new(self), remaining)),
+            None => Err(()),
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    ///Decrements self, returning time spent blocked.
+    ///
+    ///Returns zero if permit was acquired without blocking, otherwise time elapsed while awaiting signal,
+    ///which can be recorded into histogram of acquisition latency.
+    pub fn wait_timed(&self) -> time::Duration {
+        if self.try_wait() {
+            return time::Duration::from_secs(0);
+        }
+
+        let start = Instant::now();
+        self.wait();
+        start.elapsed()
+    }
+}
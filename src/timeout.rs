@@ -0,0 +1,62 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///Describes how to handle timeout, which exceeds maximum supported by platform.
+pub enum TimeoutPolicy {
+    ///Wait for maximum supported timeout instead.
+    ///
+    ///This is how `wait_timeout` behaves.
+    Saturate,
+    ///Fail with `SemError::TimeoutOverflow` without waiting.
+    Error,
+    ///Treat timeout as infinite, waiting until semaphore is signaled.
+    Infinite,
+}
+
+impl Default for TimeoutPolicy {
+    #[inline(always)]
+    fn default() -> Self {
+        TimeoutPolicy::Saturate
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///Outcome of wait with timeout.
+pub enum WaitOutcome {
+    ///Semaphore was signaled and permit is acquired.
+    Acquired,
+    ///Timeout elapsed before semaphore was signaled.
+    TimedOut,
+}
+
+impl WaitOutcome {
+    #[inline(always)]
+    ///Returns `true` if permit is acquired.
+    pub fn is_acquired(self) -> bool {
+        self == WaitOutcome::Acquired
+    }
+
+    #[inline(always)]
+    ///Returns `true` if wait timed out.
+    pub fn is_timed_out(self) -> bool {
+        self == WaitOutcome::TimedOut
+    }
+}
+
+impl From<bool> for WaitOutcome {
+    #[inline(always)]
+    fn from(acquired: bool) -> Self {
+        match acquired {
+            true => WaitOutcome::Acquired,
+            false => WaitOutcome::TimedOut,
+        }
+    }
+}
+
+impl crate::Sem {
+    #[inline]
+    ///Attempts to decrement self within provided time, returning outcome of wait.
+    ///
+    ///Same as `wait_timeout`, but with outcome that reads better than `bool`.
+    pub fn wait_timeout_outcome(&self, timeout: core::time::Duration) -> WaitOutcome {
+        self.wait_timeout(timeout).into()
+    }
+}
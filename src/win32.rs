@@ -1,39 +1,106 @@
 use core::ptr;
 use core::ffi::c_void;
-use core::sync::atomic::{AtomicPtr, Ordering};
+use core::time;
+use core::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
 
-use crate::unlikely;
+use crate::{unlikely, SemError, TimeoutPolicy};
 
 const WAIT_OBJECT_0: u32 = 0;
 const WAIT_TIMEOUT: u32 = 0x00000102;
+const WAIT_IO_COMPLETION: u32 = 0x000000C0;
+//Only mutexes can be abandoned, so it is returned only if handle is not semaphore
+const WAIT_ABANDONED: u32 = 0x00000080;
+const WAIT_FAILED: u32 = 0xFFFFFFFF;
 const INFINITE: u32 = 0xFFFFFFFF;
+//Maximum finite timeout, as INFINITE is reserved
+const MAX_WAIT_MS: u32 = INFINITE - 1;
+const ERROR_TOO_MANY_POSTS: u32 = 298;
+const MAXIMUM_WAIT_OBJECTS: usize = 64;
+const DUPLICATE_SAME_ACCESS: u32 = 0x00000002;
+//Timeouts below are too precise for WaitForSingleObject
+const SPIN_THRESHOLD: time::Duration = time::Duration::from_millis(1);
+//Sentinel handle, indicating that semaphore is being created
+const INITING: *mut c_void = 1 as *mut c_void;
+
+#[repr(C)]
+struct SecurityAttributes {
+    length: u32,
+    security_descriptor: *mut c_void,
+    inherit_handle: i32,
+}
 
 extern "system" {
     fn CloseHandle(handle: *mut c_void) -> i32;
-    fn CreateSemaphoreW(attrs: *mut c_void, initial: i32, max: i32, name: *const u16) -> *mut c_void;
+    fn CreateSemaphoreW(attrs: *mut SecurityAttributes, initial: i32, max: i32, name: *const u16) -> *mut c_void;
     fn WaitForSingleObject(handle: *mut c_void, timeout_ms: u32) -> u32;
+    fn WaitForSingleObjectEx(handle: *mut c_void, timeout_ms: u32, alertable: i32) -> u32;
+    fn WaitForMultipleObjects(count: u32, handles: *const *mut c_void, wait_all: i32, timeout_ms: u32) -> u32;
     fn ReleaseSemaphore(handle: *mut c_void, increment: i32, previous_increment: *mut i32) -> i32;
+    fn GetLastError() -> u32;
+    fn GetTickCount64() -> u64;
+    fn SwitchToThread() -> i32;
+    fn GetCurrentProcess() -> *mut c_void;
+    fn DuplicateHandle(source_process: *mut c_void, source: *mut c_void, target_process: *mut c_void, target: *mut *mut c_void, access: u32, inherit: i32, options: u32) -> i32;
+    fn QueryPerformanceFrequency(frequency: *mut i64) -> i32;
+    fn QueryPerformanceCounter(count: *mut i64) -> i32;
 }
 
 ///Windows implementation of Semaphore
 pub struct Sem {
-    handle: AtomicPtr<c_void>
+    handle: AtomicPtr<c_void>,
+    //Fair mode is ticket based: waiter takes next ticket and only awaits kernel semaphore once it is served.
+    fair: bool,
+    next_ticket: AtomicU32,
+    now_serving: AtomicU32,
+    max: u32,
 }
 
+//HANDLE is pointer
+const _: () = assert!(core::mem::size_of::<AtomicPtr<c_void>>() == core::mem::size_of::<*mut c_void>());
+const _: () = assert!(core::mem::size_of::<SecurityAttributes>() == 3 * core::mem::size_of::<usize>());
+
 impl Sem {
+    ///Maximum value of semaphore.
+    ///
+    ///Corresponds to maximum count passed to `CreateSemaphoreW`.
+    pub const MAX: u32 = i32::max_value() as u32;
+
+    ///Whether `wait_timeout` is supported.
+    pub const SUPPORTS_TIMEOUT: bool = true;
+
+    ///Granularity of timeout accepted by `wait_timeout`.
+    ///
+    ///`WaitForSingleObject` accepts milliseconds, hence longer timeouts are rounded to it.
+    pub const TIMEOUT_RESOLUTION: time::Duration = time::Duration::from_millis(1);
+
     ///Creates new uninit instance.
     ///
     ///It is UB to use it until `init` is called.
     pub const unsafe fn new_uninit() -> Self {
         Self {
-            handle: AtomicPtr::new(ptr::null_mut())
+            handle: AtomicPtr::new(ptr::null_mut()),
+            fair: false,
+            next_ticket: AtomicU32::new(0),
+            now_serving: AtomicU32::new(0),
+            max: Self::MAX,
         }
     }
 
     #[inline(always)]
     ///Returns whether semaphore is successfully initialized
     pub fn is_init(&self) -> bool {
-        !self.handle.load(Ordering::Acquire).is_null()
+        let handle = self.handle.load(Ordering::Acquire);
+        !handle.is_null() && handle != INITING
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn await_init(&self) {
+        while self.handle.load(Ordering::Acquire) == INITING {
+            unsafe {
+                SwitchToThread();
+            }
+        }
     }
 
     #[must_use]
@@ -42,26 +109,37 @@ impl Sem {
     ///Returns `true` on success.
     ///
     ///Returns `false` if semaphore is already initialized or initialization failed.
+    ///
+    ///Initialization fails if `init` is greater than maximum, which is `Sem::MAX` unless semaphore is created with `new_with_max`.
     pub fn init(&self, init: u32) -> bool {
+        self.init_with_attrs(init, ptr::null_mut())
+    }
+
+    fn init_with_attrs(&self, init: u32, attrs: *mut SecurityAttributes) -> bool {
+        if init > self.max {
+            return unlikely(false);
+        }
+
         if !self.handle.load(Ordering::Acquire).is_null() {
             //Similarly to `Once` we give priority to already-init path
             return false;
-        } else {
-            let handle = unsafe {
-                CreateSemaphoreW(ptr::null_mut(), init as i32, i32::max_value(), ptr::null())
-            };
-
-            let res = match self.handle.compare_exchange(ptr::null_mut(), handle, Ordering::SeqCst, Ordering::Acquire) {
-                Ok(_) => !handle.is_null(),
-                Err(_) => {
-                    unsafe {
-                        CloseHandle(handle);
-                    }
-                    unlikely(false)
-                }
-            };
+        }
 
-            unlikely(res)
+        //Only thread that claims semaphore creates handle, others await it
+        match self.handle.compare_exchange(ptr::null_mut(), INITING, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => {
+                let handle = unsafe {
+                    CreateSemaphoreW(attrs, init as i32, self.max as i32, ptr::null())
+                };
+                //On failure handle is null, returning semaphore into uninitialized state
+                self.handle.store(handle, Ordering::Release);
+                unlikely(!handle.is_null())
+            },
+            Err(handle) if handle == INITING => {
+                self.await_init();
+                unlikely(false)
+            },
+            Err(_) => unlikely(false),
         }
     }
 
@@ -78,21 +156,253 @@ impl Sem {
         }
     }
 
+    ///Creates new instance, initializing it with `init`, which handle is inheritable by child processes.
+    ///
+    ///Handle is inherited by child processes created with handle inheritance enabled (e.g. `std::process::Command`).
+    ///Its value, retrieved via `raw_handle`, is the same in child process and needs to be passed to it
+    ///(e.g. via command line or environment), where semaphore can be adopted using `from_inherited_handle`.
+    pub fn new_inheritable(init: u32) -> Option<Self> {
+        Self::with_options(init, Self::MAX, false, true)
+    }
+
+    ///Creates new instance, initializing it with `init`, which value never exceeds `max`.
+    ///
+    ///Returns `None` if `max` is zero, below `init` or above `Sem::MAX`, or initialization fails.
+    pub fn new_with_max(init: u32, max: u32) -> Option<Self> {
+        Self::with_options(init, max, false, false)
+    }
+
+    #[inline(always)]
+    ///Returns maximum value of semaphore.
+    ///
+    ///For semaphore adopted via `from_inherited_handle` it is always `Sem::MAX`, as actual maximum is unknown.
+    pub fn max(&self) -> u32 {
+        self.max
+    }
+
+    #[cfg(feature = "counting")]
+    #[inline(always)]
+    //Number of permits that can be added without exceeding maximum, unknown as value cannot be queried.
+    //`ReleaseSemaphore` enforces maximum on its own.
+    pub(crate) fn headroom(&self) -> Option<u32> {
+        None
+    }
+
+    pub(crate) fn with_options(init: u32, max: u32, fair: bool, inheritable: bool) -> Option<Self> {
+        let mut attrs = SecurityAttributes {
+            length: core::mem::size_of::<SecurityAttributes>() as u32,
+            security_descriptor: ptr::null_mut(),
+            inherit_handle: inheritable as i32,
+        };
+
+        Self::with_attrs(init, max, fair, &mut attrs)
+    }
+
+    fn with_attrs(init: u32, max: u32, fair: bool, attrs: *mut SecurityAttributes) -> Option<Self> {
+        if max == 0 || max > Self::MAX {
+            return unlikely(None);
+        }
+
+        let result = Self {
+            handle: AtomicPtr::new(ptr::null_mut()),
+            fair,
+            next_ticket: AtomicU32::new(0),
+            now_serving: AtomicU32::new(0),
+            max,
+        };
+
+        if result.init_with_attrs(init, attrs) {
+            Some(result)
+        } else {
+            unlikely(None)
+        }
+    }
+
+    ///Creates new instance, initializing it with `init`, which value never exceeds `max`,
+    ///passing `attrs` to `CreateSemaphoreW` as `SECURITY_ATTRIBUTES`.
+    ///
+    ///Allows to specify security descriptor (e.g. custom DACL to share semaphore with other security contexts)
+    ///and inheritability of handle. If `attrs` is null, default security descriptor is used and handle is not inheritable.
+    ///
+    ///Returns `None` if `max` is zero, below `init` or above `Sem::MAX`, or initialization fails.
+    ///
+    ///## Safety
+    ///
+    ///`attrs` must be null or point to valid `SECURITY_ATTRIBUTES` with correct `nLength`,
+    ///which `lpSecurityDescriptor` is null or points to valid security descriptor.
+    ///It is only read during this call, so it doesn't need to outlive semaphore.
+    pub unsafe fn new_with_security_attributes(init: u32, max: u32, attrs: *mut c_void) -> Option<Self> {
+        Self::with_attrs(init, max, false, attrs as *mut SecurityAttributes)
+    }
+
+    ///Creates new instance from semaphore handle inherited from parent process.
+    ///
+    ///Semaphore takes ownership of handle, closing it on `close`.
+    ///
+    ///## Safety
+    ///
+    ///`raw` must be valid semaphore handle, not owned by anything else, with at least `SEMAPHORE_MODIFY_STATE` and `SYNCHRONIZE` access.
+    pub unsafe fn from_inherited_handle(raw: usize) -> Self {
+        let result = Self::new_uninit();
+        result.handle.store(raw as *mut c_void, Ordering::Release);
+        result
+    }
+
+    #[inline]
+    ///Returns raw value of underlying handle, which is null if semaphore is not initialized.
+    pub fn raw_handle(&self) -> usize {
+        match self.is_init() {
+            true => self.handle.load(Ordering::Acquire) as usize,
+            false => 0,
+        }
+    }
+
+    ///Creates new instance referring to the same kernel semaphore, using `DuplicateHandle`.
+    ///
+    ///Returned instance owns its own handle, so either of them can be dropped independently.
+    ///Fair mode queue is not shared, hence duplicate is never in fair mode.
+    ///
+    ///Returns `None` if semaphore is not initialized or duplication fails.
+    pub fn duplicate(&self) -> Option<Self> {
+        if !self.is_init() {
+            return unlikely(None);
+        }
+
+        let mut handle = ptr::null_mut();
+        let res = unsafe {
+            let process = GetCurrentProcess();
+            DuplicateHandle(process, self.handle.load(Ordering::Acquire), process, &mut handle, 0, 0, DUPLICATE_SAME_ACCESS)
+        };
+
+        if res == 0 {
+            return unlikely(None);
+        }
+
+        Some(Self {
+            handle: AtomicPtr::new(handle),
+            fair: false,
+            next_ticket: AtomicU32::new(0),
+            now_serving: AtomicU32::new(0),
+            max: self.max,
+        })
+    }
+
+    ///Creates new instance in fair mode, initializing it with `init`
+    ///
+    ///Kernel semaphores do not guarantee order in which waiters are woken up, which can lead to starvation under heavy contention.
+    ///In fair mode waiters are queued using ticket, and only waiter at the head of queue awaits kernel semaphore, making
+    ///`wait`, `try_wait` and `wait_timeout` acquire permits in FIFO order.
+    ///
+    ///This comes at the cost of the rest of waiters busy waiting (yielding CPU) for their turn, and
+    ///`try_wait` failing whenever there are queued waiters.
+    ///Timeout of `wait_timeout` may be exceeded as waiter cannot leave queue before its turn.
+    ///Other ways to wait (e.g. `wait_any`) bypass queue.
+    pub fn new_fair(init: u32) -> Option<Self> {
+        Self::with_options(init, Self::MAX, true, false)
+    }
+
+    #[inline(always)]
+    ///Returns whether semaphore is in fair mode
+    pub fn is_fair(&self) -> bool {
+        self.fair
+    }
+
+    #[inline]
+    fn take_ticket(&self) -> u32 {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::AcqRel);
+        while self.now_serving.load(Ordering::Acquire) != ticket {
+            unsafe {
+                SwitchToThread();
+            }
+        }
+        ticket
+    }
+
+    #[inline(always)]
+    fn release_ticket(&self) {
+        self.now_serving.fetch_add(1, Ordering::Release);
+    }
+
     ///Decrements self, returning immediately if it was signaled.
     ///
     ///Otherwise awaits for signal.
     pub fn wait(&self) {
+        if self.fair {
+            self.take_ticket();
+            self.wait_kernel();
+            self.release_ticket();
+        } else {
+            self.wait_kernel();
+        }
+    }
+
+    fn wait_kernel(&self) {
         let result = unsafe {
             WaitForSingleObject(self.handle.load(Ordering::Acquire), INFINITE)
         };
 
         match result {
             WAIT_OBJECT_0 => (),
+            WAIT_ABANDONED => panic!("Handle is abandoned mutex, not semaphore"),
             //We cannot really timeout when there is no timeout
             other => panic!("Unexpected result: {}", other),
         }
     }
 
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal.
+    ///
+    ///Unlike `wait`, returns error instead of panicking on failure.
+    ///In case of `WAIT_FAILED`, error is retrieved via `GetLastError`,
+    ///otherwise unexpected wait result is returned as error code.
+    ///Wait is alertable, hence APCs queued to current thread are executed while it awaits,
+    ///after which wait is retried (`WAIT_IO_COMPLETION`).
+    ///
+    ///In fair mode waiter's turn is released even on failure, so it doesn't stall the queue.
+    pub fn wait_checked(&self) -> Result<(), SemError> {
+        if !self.is_init() {
+            return Err(SemError::Uninitialized);
+        }
+
+        if self.fair {
+            self.take_ticket();
+            let result = self.wait_checked_kernel();
+            self.release_ticket();
+            result
+        } else {
+            self.wait_checked_kernel()
+        }
+    }
+
+    fn wait_checked_kernel(&self) -> Result<(), SemError> {
+        loop {
+            let result = unsafe {
+                WaitForSingleObjectEx(self.handle.load(Ordering::Acquire), INFINITE, 1)
+            };
+
+            match result {
+                WAIT_OBJECT_0 => break Ok(()),
+                WAIT_IO_COMPLETION => continue,
+                WAIT_ABANDONED => break Err(SemError::Abandoned),
+                WAIT_FAILED => break Err(SemError::Os(unsafe { GetLastError() } as i32)),
+                other => break Err(SemError::Os(other as i32)),
+            }
+        }
+    }
+
+    #[inline]
+    ///Decrements self, returning immediately if it was signaled.
+    ///
+    ///Otherwise awaits for signal.
+    ///
+    ///Returns number of times wait was interrupted and retried.
+    ///Non-alertable wait cannot be interrupted on Windows, hence it is always `0`.
+    pub fn wait_counting_interrupts(&self) -> u32 {
+        self.wait();
+        0
+    }
+
     #[inline]
     ///Attempts to decrement self, returning whether self was signaled or not.
     ///
@@ -100,7 +410,63 @@ impl Sem {
     ///
     ///Returns `false` otherwise.
     pub fn try_wait(&self) -> bool {
-        self.wait_timeout(core::time::Duration::from_secs(0))
+        if self.fair {
+            //Only proceed if there is nobody in queue
+            let serving = self.now_serving.load(Ordering::Acquire);
+            if self.next_ticket.compare_exchange(serving, serving.wrapping_add(1), Ordering::AcqRel, Ordering::Relaxed).is_err() {
+                return false;
+            }
+
+            let result = self.wait_timeout_kernel(time::Duration::from_secs(0));
+            self.release_ticket();
+            result
+        } else {
+            self.wait_timeout_kernel(time::Duration::from_secs(0))
+        }
+    }
+
+    ///Attempts to decrement self, returning whether self was signaled or not.
+    ///
+    ///Unlike `try_wait`, distinguishes empty semaphore from failure.
+    ///In fair mode, queued waiters are treated as empty semaphore.
+    ///
+    ///Returns `Ok(true)` if self was signaled.
+    ///
+    ///Returns `Ok(false)` if self was not signaled (`WAIT_TIMEOUT`).
+    ///
+    ///Returns `Err` on failure, which is retrieved via `GetLastError` in case of `WAIT_FAILED`,
+    ///otherwise unexpected wait result is returned as error code.
+    pub fn try_wait_checked(&self) -> Result<bool, SemError> {
+        if !self.is_init() {
+            return Err(SemError::Uninitialized);
+        }
+
+        if self.fair {
+            let serving = self.now_serving.load(Ordering::Acquire);
+            if self.next_ticket.compare_exchange(serving, serving.wrapping_add(1), Ordering::AcqRel, Ordering::Relaxed).is_err() {
+                return Ok(false);
+            }
+
+            let result = self.try_wait_checked_kernel();
+            self.release_ticket();
+            result
+        } else {
+            self.try_wait_checked_kernel()
+        }
+    }
+
+    fn try_wait_checked_kernel(&self) -> Result<bool, SemError> {
+        let result = unsafe {
+            WaitForSingleObject(self.handle.load(Ordering::Acquire), 0)
+        };
+
+        match result {
+            WAIT_OBJECT_0 => Ok(true),
+            WAIT_TIMEOUT => Ok(false),
+            WAIT_ABANDONED => Err(SemError::Abandoned),
+            WAIT_FAILED => Err(SemError::Os(unsafe { GetLastError() } as i32)),
+            other => Err(SemError::Os(other as i32)),
+        }
     }
 
     ///Attempts to decrement self within provided time, returning whether self was signaled or not.
@@ -108,17 +474,165 @@ impl Sem {
     ///Returns `true` if self was signaled within specified timeout
     ///
     ///Returns `false` otherwise
-    pub fn wait_timeout(&self, timeout: core::time::Duration) -> bool {
-        use core::convert::TryInto;
+    ///
+    ///`WaitForSingleObject` accepts at most `u32::MAX - 1` milliseconds (~49.7 days),
+    ///hence longer timeouts are awaited in multiple consecutive waits until whole `timeout` elapses.
+    ///
+    ///As `WaitForSingleObject` has millisecond granularity, non-zero timeouts below 1ms are awaited by
+    ///busy-waiting on semaphore using `QueryPerformanceCounter` to track time, instead of entering kernel wait.
+    ///This keeps CPU busy for duration of timeout, which is bounded by 1ms.
+    ///
+    ///Zero timeout is equivalent to `try_wait`, hence in fair mode it doesn't queue behind other waiters.
+    pub fn wait_timeout(&self, timeout: time::Duration) -> bool {
+        if timeout == time::Duration::from_secs(0) {
+            return self.try_wait();
+        }
+
+        if self.fair {
+            let start = unsafe {
+                GetTickCount64()
+            };
+            self.take_ticket();
+            let elapsed = unsafe {
+                GetTickCount64()
+            } - start;
+
+            let remaining = timeout.checked_sub(time::Duration::from_millis(elapsed)).unwrap_or(time::Duration::from_secs(0));
+            let result = self.wait_timeout_kernel(remaining);
+            self.release_ticket();
+            result
+        } else {
+            self.wait_timeout_kernel(timeout)
+        }
+    }
+
+    #[inline]
+    ///Attempts to decrement self within provided time, returning whether self was signaled or not.
+    ///
+    ///Any timeout can be awaited on Windows, hence `policy` is never applied and error is never returned.
+    ///
+    ///Returns `Ok(true)` if self was signaled within specified timeout
+    ///
+    ///Returns `Ok(false)` otherwise
+    pub fn wait_timeout_with_policy(&self, timeout: time::Duration, _policy: TimeoutPolicy) -> Result<bool, SemError> {
+        Ok(self.wait_timeout(timeout))
+    }
+
+    fn wait_timeout_spin(&self, timeout: time::Duration) -> bool {
+        let mut frequency = 0;
+        let mut start = 0;
+        unsafe {
+            QueryPerformanceFrequency(&mut frequency);
+            QueryPerformanceCounter(&mut start);
+        }
+        let ticks = timeout.as_nanos() * frequency as u128 / 1_000_000_000;
+
+        loop {
+            if self.wait_timeout_kernel(time::Duration::from_secs(0)) {
+                break true;
+            }
+
+            let mut now = 0;
+            unsafe {
+                QueryPerformanceCounter(&mut now);
+            }
+            if (now - start) as u128 >= ticks {
+                break false;
+            }
+
+            core::hint::spin_loop();
+        }
+    }
+
+    fn wait_timeout_kernel(&self, timeout: time::Duration) -> bool {
+        if timeout < SPIN_THRESHOLD && timeout.subsec_nanos() > 0 {
+            return self.wait_timeout_spin(timeout);
+        }
+
+        let mut remaining = timeout.as_millis();
+
+        loop {
+            let chunk = if remaining > MAX_WAIT_MS as u128 {
+                MAX_WAIT_MS
+            } else {
+                remaining as u32
+            };
+
+            let result = unsafe {
+                WaitForSingleObject(self.handle.load(Ordering::Acquire), chunk)
+            };
+
+            match result {
+                WAIT_OBJECT_0 => break true,
+                WAIT_TIMEOUT => {
+                    remaining -= chunk as u128;
+                    if remaining == 0 {
+                        break false;
+                    }
+                },
+                WAIT_ABANDONED => panic!("Handle is abandoned mutex, not semaphore"),
+                other => panic!("Unexpected result: {}", other),
+            }
+        }
+    }
+
+    ///Awaits for any of `sems` to become signaled, decrementing it.
+    ///
+    ///Returns index of decremented semaphore.
+    ///
+    ///Uses `WaitForMultipleObjects`, which prefers semaphore with lowest index when several are
+    ///signaled at the same time.
+    ///
+    ///## Panics
+    ///
+    ///If `sems` is empty or contains more than 64 semaphores.
+    pub fn wait_any(sems: &[&Sem]) -> usize {
+        assert!(!sems.is_empty(), "wait_any() requires at least one semaphore");
+        assert!(sems.len() <= MAXIMUM_WAIT_OBJECTS, "wait_any() supports at most 64 semaphores");
+
+        let mut handles = [ptr::null_mut(); MAXIMUM_WAIT_OBJECTS];
+        for (handle, sem) in handles.iter_mut().zip(sems.iter()) {
+            *handle = sem.handle.load(Ordering::Acquire);
+        }
 
         let result = unsafe {
-            WaitForSingleObject(self.handle.load(Ordering::Acquire), timeout.as_millis().try_into().unwrap_or(u32::max_value()))
+            WaitForMultipleObjects(sems.len() as u32, handles.as_ptr(), 0, INFINITE)
         };
 
-        match result {
-            WAIT_OBJECT_0 => true,
-            WAIT_TIMEOUT => false,
-            other => panic!("Unexpected result: {}", other),
+        match result.wrapping_sub(WAIT_OBJECT_0) as usize {
+            idx if idx < sems.len() => idx,
+            _ => panic!("Unexpected result: {}", result),
+        }
+    }
+
+    ///Awaits for all of `sems` to become signaled, atomically decrementing each of them once.
+    ///
+    ///Uses `WaitForMultipleObjects`, which acquires permits only when all of them are available,
+    ///avoiding deadlock of sequential acquisition.
+    ///
+    ///## Panics
+    ///
+    ///If `sems` is empty, contains more than 64 semaphores or the same semaphore multiple times.
+    pub fn wait_all(sems: &[&Sem]) {
+        assert!(!sems.is_empty(), "wait_all() requires at least one semaphore");
+        assert!(sems.len() <= MAXIMUM_WAIT_OBJECTS, "wait_all() supports at most 64 semaphores");
+        //WaitForMultipleObjects rejects duplicate handles with ERROR_INVALID_PARAMETER
+        for (idx, sem) in sems.iter().enumerate() {
+            assert!(!sems[..idx].iter().any(|other| ptr::eq(*other, *sem)), "wait_all() requires distinct semaphores");
+        }
+
+        let mut handles = [ptr::null_mut(); MAXIMUM_WAIT_OBJECTS];
+        for (handle, sem) in handles.iter_mut().zip(sems.iter()) {
+            *handle = sem.handle.load(Ordering::Acquire);
+        }
+
+        let result = unsafe {
+            WaitForMultipleObjects(sems.len() as u32, handles.as_ptr(), 1, INFINITE)
+        };
+
+        match result.wrapping_sub(WAIT_OBJECT_0) as usize {
+            idx if idx < sems.len() => (),
+            _ => panic!("Unexpected result: {}", result),
         }
     }
 
@@ -127,16 +641,85 @@ impl Sem {
         let res = unsafe {
             ReleaseSemaphore(self.handle.load(Ordering::Acquire), 1, ptr::null_mut())
         };
-        debug_assert_ne!(res, 0);
+        strict_assert_ne!(res, 0);
+    }
+
+    #[inline]
+    ///Increments self, waking any awaiting thread as result.
+    ///
+    ///Performs only `ReleaseSemaphore` without any assertions or panics.
+    ///Windows has no POSIX signals, but it can be used from console control handler the same way.
+    pub fn signal_from_handler(&self) {
+        unsafe {
+            ReleaseSemaphore(self.handle.load(Ordering::Acquire), 1, ptr::null_mut());
+        }
+    }
+
+    ///Increments self, waking any awaiting thread as result.
+    ///
+    ///Unlike `signal`, reports failure to increment, in which case permit is not added.
+    ///
+    ///Returns `SemError::Overflow` if semaphore is already at its maximum.
+    pub fn signal_checked(&self) -> Result<(), SemError> {
+        if !self.is_init() {
+            return Err(SemError::Uninitialized);
+        }
+
+        let res = unsafe {
+            ReleaseSemaphore(self.handle.load(Ordering::Acquire), 1, ptr::null_mut())
+        };
+
+        match res {
+            0 => match unsafe { GetLastError() } {
+                ERROR_TOO_MANY_POSTS => Err(SemError::Overflow),
+                code => Err(SemError::Os(code as i32)),
+            },
+            _ => Ok(()),
+        }
     }
 
+    ///Increments self `count` times, waking awaiting threads as result.
+    ///
+    ///`count` above `Sem::MAX` is misuse, in which case semaphore is not incremented.
+    pub fn signal_many(&self, count: u32) {
+        strict_assert!(count <= Self::MAX, "Semaphore value would exceed Sem::MAX");
+
+        //ReleaseSemaphore fails on zero count, while count above `Sem::MAX` would become negative `LONG`
+        if count == 0 || count > Self::MAX {
+            return;
+        }
+
+        let res = unsafe {
+            ReleaseSemaphore(self.handle.load(Ordering::Acquire), count as i32, ptr::null_mut())
+        };
+        strict_assert_ne!(res, 0);
+    }
+
+
+    #[inline]
+    ///Consumes self without performing deinitialization.
+    ///
+    ///Handle is never closed, keeping kernel object alive until process exits.
+    ///Cleanup becomes responsibility of whoever owns underlying semaphore, if anyone.
+    ///As `Drop` is not run, there is no risk of closing semaphore twice.
+    pub fn leak(self) {
+        core::mem::forget(self);
+    }
 
     ///Performs deinitialization.
     ///
     ///Using `Sem` after `close` is undefined behaviour, unless `init` is called
+    ///
+    ///Semaphore returns to uninitialized state, after which `is_init` returns `false` and `init` can be called again.
+    ///Closing uninitialized semaphore does nothing, so `close` can be safely called multiple times.
     pub unsafe fn close(&self) {
-        let handle = self.handle.swap(ptr::null_mut(), Ordering::AcqRel);
-        if !handle.is_null() {
+        //Similarly to POSIX, semaphore which is being initialized is not initialized yet, so it is left alone
+        let handle = self.handle.load(Ordering::Acquire);
+        if handle.is_null() || handle == INITING {
+            return;
+        }
+
+        if self.handle.compare_exchange(handle, ptr::null_mut(), Ordering::AcqRel, Ordering::Relaxed).is_ok() {
             CloseHandle(handle);
         }
     }
@@ -150,5 +733,14 @@ impl Drop for Sem {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::os::windows::io::AsRawHandle for Sem {
+    #[inline]
+    ///Returns underlying handle, which is null if semaphore is not initialized.
+    fn as_raw_handle(&self) -> std::os::windows::io::RawHandle {
+        self.raw_handle() as std::os::windows::io::RawHandle
+    }
+}
+
 unsafe impl Send for Sem {}
 unsafe impl Sync for Sem {}
@@ -0,0 +1,168 @@
+#![cfg(feature = "async")]
+
+use semka::async_counting::Sem;
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::Wake;
+
+struct ThreadWaker(std::thread::Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = core::pin::pin!(fut);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(result) => break result,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn should_count_permits() {
+    static SEM: Sem = Sem::new(1);
+
+    block_on(SEM.wait_async());
+    assert!(!SEM.try_wait());
+
+    SEM.signal();
+    SEM.signal_many(2);
+    assert_eq!(SEM.value(), 3);
+    block_on(SEM.wait_async());
+    assert!(SEM.try_wait());
+    assert!(SEM.try_wait());
+    assert!(!SEM.try_wait());
+}
+
+#[test]
+fn should_wake_waiting_task() {
+    let sem = Sem::new(0);
+    let counter = Arc::new(CountingWaker::default());
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let mut wait = Box::pin(sem.wait_async());
+    assert!(Pin::new(&mut wait).poll(&mut cx).is_pending());
+    assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+
+    sem.signal();
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    assert!(Pin::new(&mut wait).poll(&mut cx).is_ready());
+    assert_eq!(sem.value(), 0);
+}
+
+#[test]
+fn should_pass_notification_on_cancel() {
+    let sem = Sem::new(0);
+    let first_counter = Arc::new(CountingWaker::default());
+    let first_waker = Waker::from(first_counter.clone());
+    let second_counter = Arc::new(CountingWaker::default());
+    let second_waker = Waker::from(second_counter.clone());
+
+    let mut first = Box::pin(sem.wait_async());
+    let mut second = Box::pin(sem.wait_async());
+    assert!(Pin::new(&mut first).poll(&mut Context::from_waker(&first_waker)).is_pending());
+    assert!(Pin::new(&mut second).poll(&mut Context::from_waker(&second_waker)).is_pending());
+
+    sem.signal();
+    assert_eq!(first_counter.0.load(Ordering::SeqCst), 1);
+    assert_eq!(second_counter.0.load(Ordering::SeqCst), 0);
+
+    //Cancelled before it could take permit
+    drop(first);
+    assert_eq!(second_counter.0.load(Ordering::SeqCst), 1);
+    assert!(Pin::new(&mut second).poll(&mut Context::from_waker(&second_waker)).is_ready());
+    assert_eq!(sem.value(), 0);
+}
+
+#[test]
+fn should_pass_permits_between_threads() {
+    const THREADS: u32 = 4;
+    const ITERATIONS: u32 = 10_000;
+
+    let sem = Sem::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| block_on(async {
+                for _ in 0..ITERATIONS {
+                    sem.wait_async().await;
+                }
+            }));
+        }
+
+        for _ in 0..THREADS * ITERATIONS {
+            sem.signal();
+        }
+    });
+
+    assert_eq!(sem.value(), 0);
+}
+
+#[test]
+fn should_release_guard_on_drop() {
+    let sem = Sem::new(1);
+
+    let guard = block_on(sem.acquire_async());
+    assert_eq!(sem.value(), 0);
+    assert!(sem.try_acquire().is_none());
+    drop(guard);
+    assert_eq!(sem.value(), 1);
+
+    let guard = sem.try_acquire().unwrap();
+    assert_eq!(sem.value(), 0);
+    //Dropped on different thread than the one that acquired it
+    std::thread::scope(|scope| {
+        scope.spawn(move || drop(guard));
+    });
+    assert_eq!(sem.value(), 1);
+}
+
+#[test]
+fn should_release_guard_on_task_cancel() {
+    let sem = Sem::new(1);
+    let counter = Arc::new(CountingWaker::default());
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    //Task acquires permit, then gets stuck at await point, holding it
+    let mut task = Box::pin(async {
+        let _guard = sem.acquire_async().await;
+        core::future::pending::<()>().await;
+    });
+    assert!(task.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(sem.value(), 0);
+
+    let mut waiting = Box::pin(sem.acquire_async());
+    assert!(Pin::new(&mut waiting).poll(&mut cx).is_pending());
+
+    drop(task);
+    assert_eq!(sem.value(), 1);
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    match Pin::new(&mut waiting).poll(&mut cx) {
+        Poll::Ready(guard) => assert!(core::ptr::eq(guard.sem(), &sem)),
+        Poll::Pending => panic!("Permit of cancelled task is not returned"),
+    }
+    assert_eq!(sem.value(), 1);
+}
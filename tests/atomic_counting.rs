@@ -0,0 +1,167 @@
+use semka::atomic_counting::Sem;
+
+#[test]
+fn should_count_permits() {
+    static SEM: Sem = Sem::new(1);
+
+    assert!(SEM.try_wait());
+    assert!(!SEM.try_wait());
+
+    SEM.signal();
+    SEM.signal_many(2);
+    assert_eq!(SEM.value(), 3);
+    SEM.wait();
+    assert!(SEM.try_wait());
+    assert!(SEM.try_wait());
+    assert!(!SEM.try_wait());
+}
+
+#[test]
+fn should_wait_for_signal_across_threads() {
+    const THREADS: u32 = 4;
+    const ITERATIONS: u32 = 1000;
+
+    let sem = Sem::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    sem.wait();
+                }
+            });
+        }
+
+        for _ in 0..THREADS * ITERATIONS {
+            sem.signal();
+        }
+    });
+
+    assert_eq!(sem.value(), 0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn should_timeout_on_wait() {
+    use std::time;
+
+    let sem = Sem::new(0);
+
+    let before = time::Instant::now();
+    assert!(!sem.wait_timeout(time::Duration::from_millis(50)));
+    assert!(before.elapsed() >= time::Duration::from_millis(50));
+
+    sem.signal();
+    assert!(sem.wait_timeout(time::Duration::from_millis(50)));
+}
+
+#[test]
+fn should_give_up_after_spin_budget() {
+    let sem = Sem::new(0);
+
+    assert!(!sem.wait_timeout_with(0));
+    assert!(!sem.wait_timeout_with(1000));
+
+    sem.signal();
+    assert!(sem.wait_timeout_with(0));
+    sem.signal();
+    assert!(sem.wait_timeout_with(1000));
+    assert_eq!(sem.value(), 0);
+}
+
+#[test]
+fn should_attempt_once_with_zero_spins() {
+    let sem = Sem::new(1);
+
+    assert!(sem.wait_timeout_with(0));
+    assert_eq!(sem.value(), 0);
+    assert!(!sem.wait_timeout_with(0));
+
+    sem.signal_many(2);
+    assert!(sem.wait_timeout_with(0));
+    assert_eq!(sem.value(), 1);
+}
+
+#[test]
+fn should_try_wait_many_atomically() {
+    const THREADS: u32 = 4;
+    const PERMITS: u32 = 3;
+    const ITERATIONS: u32 = 10_000;
+
+    let sem = Sem::new(PERMITS * 2);
+    assert!(!sem.try_wait_many(PERMITS * 2 + 1));
+    assert_eq!(sem.value(), PERMITS * 2);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    if sem.try_wait_many(PERMITS) {
+                        let value = sem.value();
+                        assert!(value <= PERMITS, "value={}", value);
+                        sem.signal_many(PERMITS);
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(sem.value(), PERMITS * 2);
+}
+
+#[test]
+fn should_acquire_after_lock_is_held_for_a_while() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time;
+
+    let lock = Sem::new(1);
+    let held = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        lock.wait();
+        held.store(true, Ordering::SeqCst);
+
+        let waiter = scope.spawn(|| {
+            lock.wait();
+            let acquired_while_held = held.load(Ordering::SeqCst);
+            lock.signal();
+            acquired_while_held
+        });
+
+        std::thread::sleep(time::Duration::from_millis(100));
+        held.store(false, Ordering::SeqCst);
+        lock.signal();
+
+        assert!(!waiter.join().unwrap());
+    });
+
+    assert_eq!(lock.value(), 1);
+}
+
+#[test]
+fn should_wait_with_spin_strategy() {
+    use semka::spin::{Backoff, PureSpin, SpinStrategy};
+
+    fn hand_over<S: SpinStrategy + Sync>(strategy: &S) {
+        const ITERATIONS: u32 = 1000;
+
+        let sem = Sem::new(0);
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    sem.wait_with_strategy(strategy);
+                }
+            });
+
+            for _ in 0..ITERATIONS {
+                sem.signal();
+            }
+        });
+        assert_eq!(sem.value(), 0);
+    }
+
+    hand_over(&PureSpin);
+    hand_over(&Backoff);
+    #[cfg(feature = "std")]
+    hand_over(&semka::spin::Yield);
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "counting")]
+
+use semka::BoundedCounter;
+use std::sync::Mutex;
+
+//Ring buffer with slots coordinated by BoundedCounter, while indexes are guarded by mutex
+struct Ring {
+    counter: BoundedCounter,
+    slots: Mutex<([u32; 4], usize, usize)>,
+}
+
+impl Ring {
+    fn new() -> Self {
+        Self {
+            counter: BoundedCounter::new(4).unwrap(),
+            slots: Mutex::new(([0; 4], 0, 0)),
+        }
+    }
+
+    fn push(&self, value: u32) {
+        let slot = self.counter.produce();
+        {
+            let mut slots = self.slots.lock().unwrap();
+            let idx = slots.1;
+            slots.0[idx] = value;
+            slots.1 = (idx + 1) % 4;
+        }
+        slot.commit();
+    }
+
+    fn pop(&self) -> u32 {
+        let _slot = self.counter.consume();
+        let mut slots = self.slots.lock().unwrap();
+        let idx = slots.2;
+        slots.2 = (idx + 1) % 4;
+        slots.0[idx]
+    }
+}
+
+#[test]
+fn should_coordinate_slots() {
+    let counter = BoundedCounter::new(2).unwrap();
+    assert_eq!(counter.capacity(), 2);
+    assert!(counter.try_consume().is_none());
+
+    counter.acquire_empty();
+    counter.release_filled();
+    counter.produce().commit();
+    assert!(counter.try_produce().is_none());
+
+    //Dropping uncommitted slot returns it as empty
+    counter.acquire_filled();
+    counter.release_empty();
+    drop(counter.try_produce().unwrap());
+
+    assert!(counter.try_acquire_filled());
+    assert!(!counter.try_acquire_filled());
+    counter.release_empty();
+    assert!(counter.try_acquire_empty());
+    assert!(counter.try_acquire_empty());
+    assert!(!counter.try_acquire_empty());
+}
+
+#[test]
+fn should_model_ring_buffer() {
+    const ITEMS: u32 = 10_000;
+
+    let ring = Ring::new();
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for value in 0..ITEMS {
+                ring.push(value);
+            }
+        });
+
+        for expected in 0..ITEMS {
+            assert_eq!(ring.pop(), expected);
+        }
+    });
+
+    assert!(ring.counter.try_consume().is_none());
+    for _ in 0..4 {
+        ring.counter.try_produce().unwrap().commit();
+    }
+    assert!(ring.counter.try_produce().is_none());
+}
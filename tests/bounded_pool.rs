@@ -0,0 +1,62 @@
+#![cfg(all(feature = "std", feature = "counting"))]
+
+use semka::BoundedPool;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time;
+
+#[test]
+fn should_never_exceed_capacity() {
+    const CAPACITY: u32 = 3;
+    const TASKS: u32 = 32;
+
+    let pool = BoundedPool::new(CAPACITY).unwrap();
+    assert_eq!(pool.capacity(), CAPACITY);
+
+    let running = Arc::new(AtomicU32::new(0));
+    let max_running = Arc::new(AtomicU32::new(0));
+
+    let handles: Vec<_> = (0..TASKS).map(|_| {
+        let running = running.clone();
+        let max_running = max_running.clone();
+        pool.spawn(move || {
+            let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(current, Ordering::SeqCst);
+            std::thread::sleep(time::Duration::from_millis(2));
+            running.fetch_sub(1, Ordering::SeqCst);
+        })
+    }).collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert!(max_running.load(Ordering::SeqCst) <= CAPACITY);
+    assert_eq!(running.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn should_release_permit_on_panic() {
+    let pool = BoundedPool::new(1).unwrap();
+
+    let handle = pool.spawn(|| panic!("task failed"));
+    assert!(handle.join().is_err());
+
+    let handle = pool.try_spawn(|| ()).unwrap();
+    handle.join().unwrap();
+}
+
+#[test]
+fn should_not_spawn_when_full() {
+    let pool = BoundedPool::new(1).unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel::<()>();
+
+    let handle = pool.spawn(move || {
+        let _ = receiver.recv();
+    });
+    assert!(pool.try_spawn(|| ()).is_none());
+
+    drop(sender);
+    handle.join().unwrap();
+    pool.try_spawn(|| ()).unwrap().join().unwrap();
+}
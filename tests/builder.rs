@@ -0,0 +1,157 @@
+use semka::SemBuilder;
+
+#[test]
+fn should_build_with_defaults() {
+    let sem = SemBuilder::default().build().unwrap();
+    assert!(sem.is_init());
+    assert!(!sem.try_wait());
+}
+
+#[test]
+fn should_build_with_options() {
+    let sem = SemBuilder::new().init(2).max(4).fair(true).inheritable(false).build().unwrap();
+    assert!(sem.try_wait());
+    assert!(sem.try_wait());
+    assert!(!sem.try_wait());
+    #[cfg(windows)]
+    assert!(sem.is_fair());
+}
+
+#[test]
+fn should_fail_with_invalid_max() {
+    assert!(SemBuilder::new().max(0).build().is_none());
+    assert!(SemBuilder::new().init(2).max(1).build().is_none());
+    assert!(SemBuilder::new().max(semka::Sem::MAX + 1).build().is_none());
+}
+
+#[cfg(windows)]
+#[test]
+fn should_limit_value_by_max() {
+    let sem = SemBuilder::new().init(1).max(1).build().unwrap();
+    assert_eq!(sem.signal_checked(), Err(semka::SemError::Overflow));
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+#[test]
+fn should_build_with_policy() {
+    let sem = SemBuilder::new().init(1).policy(semka::SyncPolicy::Lifo).build().unwrap();
+    assert!(sem.try_wait());
+}
+
+#[test]
+fn should_create_signalled() {
+    use semka::Sem;
+
+    let sem = Sem::new_signalled(1, 2).unwrap();
+    assert!(sem.try_wait());
+    assert!(sem.try_wait());
+    assert!(sem.try_wait());
+    assert!(!sem.try_wait());
+
+    assert!(Sem::new_signalled(u32::MAX, 1).is_none());
+    assert!(Sem::new_signalled(Sem::MAX, 1).is_none());
+}
+
+#[test]
+fn should_respect_max_on_over_signal() {
+    use semka::{Sem, SemError};
+
+    const MAX: u32 = 3;
+
+    let sem = Sem::new_with_max(1, MAX).unwrap();
+    assert_eq!(sem.max(), MAX);
+    assert_eq!(sem.signal_checked(), Ok(()));
+    assert_eq!(sem.signal_checked(), Ok(()));
+    assert_eq!(sem.signal_checked(), Err(SemError::Overflow));
+
+    assert!(sem.try_wait());
+    assert_eq!(sem.signal_checked(), Ok(()));
+    assert_eq!(sem.signal_checked(), Err(SemError::Overflow));
+
+    for _ in 0..MAX {
+        assert!(sem.try_wait());
+    }
+    assert!(!sem.try_wait());
+
+    assert!(Sem::new_with_max(2, 1).is_none());
+    assert!(Sem::new_with_max(0, 0).is_none());
+    assert_eq!(Sem::new(0).unwrap().max(), Sem::MAX);
+}
+
+#[test]
+fn should_respect_max_on_concurrent_over_signal() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const MAX: u32 = 16;
+    const THREADS: u32 = 8;
+
+    let sem = SemBuilder::new().max(MAX).build().unwrap();
+    let signaled = AtomicU32::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..MAX {
+                    if sem.signal_checked().is_ok() {
+                        signaled.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(signaled.load(Ordering::Relaxed), MAX);
+    let mut acquired = 0;
+    while sem.try_wait() {
+        acquired += 1;
+    }
+    assert_eq!(acquired, MAX);
+}
+
+#[test]
+fn should_create_array() {
+    use semka::Sem;
+
+    let sems = Sem::new_array::<4>(1).unwrap();
+    for sem in sems.iter() {
+        assert!(sem.is_init());
+        assert!(sem.try_wait());
+        assert!(!sem.try_wait());
+    }
+    drop(sems);
+
+    assert!(Sem::new_array::<4>(Sem::MAX + 1).is_none());
+    assert_eq!(Sem::new_array::<0>(0).unwrap().len(), 0);
+}
+
+#[cfg(feature = "counting")]
+#[test]
+fn should_signal_saturating_up_to_max() {
+    use semka::Sem;
+
+    let sem = Sem::new_with_max(1, 4).unwrap();
+
+    assert_eq!(sem.signal_saturating(2), 2);
+    assert_eq!(sem.signal_saturating(5), 1);
+    assert_eq!(sem.signal_saturating(1), 0);
+
+    for _ in 0..4 {
+        assert!(sem.try_wait());
+    }
+    assert!(!sem.try_wait());
+
+    assert_eq!(sem.signal_saturating(0), 0);
+    assert_eq!(sem.signal_saturating(3), 3);
+    assert!(sem.try_wait());
+    assert_eq!(sem.signal_saturating(2), 2);
+
+    let unlimited = Sem::new(0).unwrap();
+    assert_eq!(unlimited.signal_saturating(100), 100);
+
+    //Mach semaphore tracks maximum only when it is explicitly set
+    if cfg!(not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))) {
+        let almost_full = Sem::new(Sem::MAX - 2).unwrap();
+        assert_eq!(almost_full.signal_saturating(10), 2);
+        assert_eq!(almost_full.signal_saturating(1), 0);
+    }
+}
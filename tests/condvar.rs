@@ -0,0 +1,64 @@
+#![cfg(feature = "condvar")]
+
+use semka::Semaphore;
+use std::time;
+
+//Same scenarios are run against native semaphore and condvar based one, expecting identical behavior.
+fn check_counting<S: Semaphore + Sync>(sem: &S) {
+    assert!(sem.try_wait());
+    assert!(!sem.try_wait());
+    assert!(!sem.wait_timeout(time::Duration::from_millis(10)));
+
+    sem.signal();
+    sem.wait();
+    assert!(!sem.try_wait());
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(time::Duration::from_millis(10));
+            sem.signal();
+        });
+
+        assert!(sem.wait_timeout(time::Duration::from_secs(5)));
+    });
+}
+
+fn check_timeout_accuracy<S: Semaphore>(sem: &S) -> time::Duration {
+    const TIMEOUT: time::Duration = time::Duration::from_millis(50);
+
+    let before = time::Instant::now();
+    assert!(!sem.wait_timeout(TIMEOUT));
+    let elapsed = before.elapsed();
+    assert!(elapsed >= TIMEOUT);
+    elapsed
+}
+
+#[test]
+fn should_behave_as_native_semaphore() {
+    let native = semka::Sem::new(1).unwrap();
+    let condvar = semka::condvar::Sem::new(1);
+
+    check_counting(&native);
+    check_counting(&condvar);
+
+    let native = check_timeout_accuracy(&native);
+    let condvar = check_timeout_accuracy(&condvar);
+    println!("timeout 50ms: native={:?} condvar={:?}", native, condvar);
+}
+
+#[test]
+fn should_wake_all_waiters_with_signal_many() {
+    const THREADS: u32 = 4;
+
+    let sem = semka::condvar::Sem::new(0);
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| sem.wait());
+        }
+
+        std::thread::sleep(time::Duration::from_millis(20));
+        sem.signal_many(THREADS);
+    });
+
+    assert_eq!(sem.value(), 0);
+}
@@ -0,0 +1,95 @@
+#![cfg(any(target_os = "linux", target_os = "android"))]
+
+use semka::EventfdSem;
+use std::time;
+
+#[test]
+fn should_count_permits() {
+    let sem = EventfdSem::new(1).unwrap();
+    assert!(sem.as_raw_fd() >= 0);
+
+    assert!(sem.try_wait());
+    assert!(!sem.try_wait());
+
+    sem.signal();
+    sem.signal_many(2);
+    sem.wait();
+    assert!(sem.try_wait());
+    assert!(sem.wait_timeout(time::Duration::from_millis(10)));
+    assert!(!sem.try_wait());
+}
+
+#[test]
+fn should_wait_timeout() {
+    let sem = EventfdSem::new(0).unwrap();
+
+    let before = time::Instant::now();
+    assert!(!sem.wait_timeout(time::Duration::from_millis(50)));
+    assert!(before.elapsed() >= time::Duration::from_millis(50));
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(time::Duration::from_millis(50));
+            sem.signal();
+        });
+
+        assert!(sem.wait_timeout(time::Duration::from_secs(5)));
+    });
+}
+
+#[test]
+fn should_be_readable_when_signaled() {
+    let sem = EventfdSem::new(0).unwrap();
+    let mut fd = libc::pollfd {
+        fd: sem.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+
+    assert_eq!(unsafe { libc::poll(&mut fd, 1, 0) }, 0);
+    sem.signal();
+    assert_eq!(unsafe { libc::poll(&mut fd, 1, 0) }, 1);
+    assert!(sem.try_wait());
+    assert_eq!(unsafe { libc::poll(&mut fd, 1, 0) }, 0);
+}
+
+#[test]
+fn should_wait_across_threads() {
+    const THREADS: u32 = 4;
+    const ITERATIONS: u32 = 1000;
+
+    let sem = EventfdSem::new(0).unwrap();
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    sem.wait();
+                }
+            });
+        }
+
+        for _ in 0..THREADS * ITERATIONS {
+            sem.signal();
+        }
+    });
+
+    assert!(!sem.try_wait());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn should_expose_raw_fd() {
+    use std::os::unix::io::AsRawFd;
+
+    let sem = EventfdSem::new(0).unwrap();
+    let fd = AsRawFd::as_raw_fd(&sem);
+    assert_eq!(fd, EventfdSem::as_raw_fd(&sem));
+
+    let value = 1u64;
+    let res = unsafe {
+        libc::write(fd, &value as *const u64 as *const _, core::mem::size_of::<u64>())
+    };
+    assert_eq!(res, core::mem::size_of::<u64>() as isize);
+    assert!(sem.try_wait());
+}
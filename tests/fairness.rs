@@ -0,0 +1,111 @@
+//!Fairness stress harness.
+//!
+//!Ignored due to its runtime, run with `cargo test --test fairness -- --ignored --nocapture`
+
+use semka::Sem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Barrier, Mutex};
+use std::time;
+
+const THREADS: usize = 8;
+//Duration of run, within which number of acquisitions per thread is measured
+const WINDOW: time::Duration = time::Duration::from_secs(2);
+//Waiting for longer means permit was lost
+const LOST_WAKEUP_TIMEOUT: time::Duration = time::Duration::from_secs(5);
+
+struct Stats {
+    min: usize,
+    max: usize,
+    //Average length of consecutive acquisitions by the same thread
+    avg_run: f64,
+}
+
+//Each thread repeatedly acquires single permit for `WINDOW`, recording order of acquisition.
+//All threads are stopped at the same time, so every thread competes with all others for the whole run,
+//and number of acquisitions per thread shows whether any of them was starved.
+fn run(name: &str, sem: &Sem) -> Stats {
+    let order = Mutex::new(Vec::new());
+    let barrier = Barrier::new(THREADS + 1);
+    let stop = AtomicBool::new(false);
+
+    let start = time::Instant::now();
+    std::thread::scope(|scope| {
+        for idx in 0..THREADS {
+            let order = &order;
+            let barrier = &barrier;
+            let stop = &stop;
+            scope.spawn(move || {
+                barrier.wait();
+                while !stop.load(Ordering::Relaxed) {
+                    assert!(sem.wait_timeout(LOST_WAKEUP_TIMEOUT), "{}: lost wakeup", name);
+                    order.lock().unwrap().push(idx);
+                    sem.signal();
+                }
+            });
+        }
+
+        barrier.wait();
+        std::thread::sleep(WINDOW);
+        stop.store(true, Ordering::Relaxed);
+    });
+    let elapsed = start.elapsed();
+
+    let order = order.into_inner().unwrap();
+
+    let mut acquired = [0usize; THREADS];
+    let mut runs = 0usize;
+    let mut prev = None;
+    for idx in order.iter().copied() {
+        acquired[idx] += 1;
+        if prev != Some(idx) {
+            runs += 1;
+        }
+        prev = Some(idx);
+    }
+
+    //Perfectly fair FIFO hands permit to next thread every time, giving equal counts and average run of 1.0,
+    //while unfair one lets the same thread reacquire.
+    let stats = Stats {
+        min: acquired.iter().copied().min().unwrap(),
+        max: acquired.iter().copied().max().unwrap(),
+        avg_run: order.len() as f64 / runs as f64,
+    };
+    println!("{:<16} elapsed={:?} min={} max={} avg_run={:.2}", name, elapsed, stats.min, stats.max, stats.avg_run);
+    stats
+}
+
+#[test]
+#[ignore]
+fn should_not_starve_waiters() {
+    let sem = Sem::new(1).unwrap();
+    let stats = run("default", &sem);
+    //No fairness is guaranteed, but every thread must make progress within window
+    assert!(stats.min > 0, "thread starved: max={}", stats.max);
+}
+
+#[cfg(windows)]
+#[test]
+#[ignore]
+fn should_hand_over_permit_in_fair_mode() {
+    let sem = Sem::new_fair(1).unwrap();
+    let stats = run("fair", &sem);
+    assert!(stats.min * 2 >= stats.max, "min={} max={}", stats.min, stats.max);
+    //Ticket queue serves waiters in order, so same thread cannot reacquire while others are queued
+    assert!(stats.avg_run < 2.0);
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+#[test]
+#[ignore]
+fn should_hand_over_permit_with_fifo_policy() {
+    use semka::SyncPolicy;
+
+    for policy in [SyncPolicy::Fifo, SyncPolicy::Lifo].iter().copied() {
+        let sem = Sem::new_with_policy(1, policy).unwrap();
+        let stats = run(&format!("{:?}", policy), &sem);
+        assert!(stats.min > 0, "{:?}: thread starved: max={}", policy, stats.max);
+        if policy == SyncPolicy::Fifo {
+            assert!(stats.min * 2 >= stats.max, "min={} max={}", stats.min, stats.max);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "futex")]
+
+use semka::futex::Sem;
+
+#[test]
+fn should_count_permits() {
+    static SEM: Sem = Sem::new(1);
+
+    assert!(SEM.try_wait());
+    assert!(!SEM.try_wait());
+
+    SEM.signal();
+    SEM.signal();
+    assert_eq!(SEM.value(), 2);
+    SEM.wait();
+    assert!(SEM.try_wait());
+    assert!(!SEM.try_wait());
+}
+
+#[test]
+fn should_wake_sleeping_waiter() {
+    let sem = Sem::new(0);
+
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| sem.wait());
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+        sem.signal();
+    });
+
+    assert_eq!(sem.value(), 0);
+}
+
+#[test]
+fn should_wait_for_signal_across_threads() {
+    const THREADS: u32 = 4;
+    const ITERATIONS: u32 = 10_000;
+
+    let sem = Sem::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    sem.wait();
+                }
+            });
+        }
+
+        for _ in 0..THREADS * ITERATIONS {
+            sem.signal();
+        }
+    });
+
+    assert_eq!(sem.value(), 0);
+}
+
+#[test]
+fn should_try_wait_many_atomically() {
+    let sem = Sem::new(3);
+
+    assert!(!sem.try_wait_many(4));
+    assert_eq!(sem.value(), 3);
+    assert!(sem.try_wait_many(2));
+    assert_eq!(sem.value(), 1);
+    assert!(sem.try_wait_many(0));
+    assert!(!sem.try_wait_many(2));
+    assert_eq!(sem.value(), 1);
+}
+
+#[test]
+fn should_not_partially_acquire_with_concurrent_try_wait_many() {
+    const THREADS: u32 = 4;
+    const PERMITS: u32 = 3;
+    const ITERATIONS: u32 = 10_000;
+
+    let sem = Sem::new(PERMITS * 2);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    if sem.try_wait_many(PERMITS) {
+                        let value = sem.value();
+                        assert!(value <= PERMITS, "value={}", value);
+                        sem.signal_many(PERMITS);
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(sem.value(), PERMITS * 2);
+}
@@ -0,0 +1,110 @@
+#![cfg(windows)]
+
+use semka::Sem;
+use std::time;
+
+const HANDLE_ENV: &str = "SEMKA_INHERITED_HANDLE";
+
+//Executed by child process only, spawned by `should_signal_parent_via_inherited_handle`
+#[test]
+fn child_signal_inherited_handle() {
+    let raw = match std::env::var(HANDLE_ENV) {
+        Ok(raw) => raw.parse::<usize>().unwrap(),
+        Err(_) => return,
+    };
+
+    let sem = unsafe {
+        Sem::from_inherited_handle(raw)
+    };
+    assert!(sem.is_init());
+    sem.signal();
+}
+
+#[test]
+fn should_signal_parent_via_inherited_handle() {
+    let sem = Sem::new_inheritable(0).unwrap();
+    assert!(!sem.try_wait());
+
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+                                      .args(["--exact", "child_signal_inherited_handle"])
+                                      .env(HANDLE_ENV, sem.raw_handle().to_string())
+                                      .status()
+                                      .unwrap();
+    assert!(status.success());
+
+    assert!(sem.wait_timeout(time::Duration::from_secs(5)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn should_expose_raw_handle() {
+    use std::os::windows::io::AsRawHandle;
+
+    let sem = Sem::new(0).unwrap();
+    let handle = sem.as_raw_handle();
+    assert_eq!(handle as usize, sem.raw_handle());
+
+    let adopted = unsafe {
+        Sem::from_inherited_handle(handle as usize)
+    };
+    adopted.signal();
+    assert!(sem.try_wait());
+    adopted.leak();
+}
+
+#[test]
+fn should_report_abandoned_mutex_handle() {
+    use core::ffi::c_void;
+    use core::ptr;
+    use semka::SemError;
+
+    extern "system" {
+        fn CreateMutexW(attrs: *mut c_void, initial_owner: i32, name: *const u16) -> *mut c_void;
+    }
+
+    //Mutex owned by thread that exits without releasing it becomes abandoned
+    let raw = std::thread::spawn(|| unsafe {
+        CreateMutexW(ptr::null_mut(), 1, ptr::null()) as usize
+    }).join().unwrap();
+    assert_ne!(raw, 0);
+
+    let sem = unsafe {
+        Sem::from_inherited_handle(raw)
+    };
+    assert_eq!(sem.wait_checked(), Err(SemError::Abandoned));
+    assert_eq!(SemError::Abandoned.to_string(), "Handle is abandoned mutex, not semaphore");
+}
+
+#[test]
+fn should_signal_parent_via_handle_created_with_security_attributes() {
+    use core::ffi::c_void;
+    use core::ptr;
+
+    #[repr(C)]
+    struct SecurityAttributes {
+        length: u32,
+        security_descriptor: *mut c_void,
+        inherit_handle: i32,
+    }
+
+    let mut attrs = SecurityAttributes {
+        length: core::mem::size_of::<SecurityAttributes>() as u32,
+        security_descriptor: ptr::null_mut(),
+        inherit_handle: 1,
+    };
+
+    let sem = unsafe {
+        Sem::new_with_security_attributes(0, 1, &mut attrs as *mut SecurityAttributes as *mut c_void)
+    }.unwrap();
+    assert_eq!(sem.max(), 1);
+    assert!(unsafe { Sem::new_with_security_attributes(2, 1, ptr::null_mut()) }.is_none());
+
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+                                      .args(["--exact", "child_signal_inherited_handle"])
+                                      .env(HANDLE_ENV, sem.raw_handle().to_string())
+                                      .status()
+                                      .unwrap();
+    assert!(status.success());
+
+    assert!(sem.wait_timeout(time::Duration::from_secs(5)));
+}
@@ -0,0 +1,128 @@
+//!Suite of pure Rust semaphores, runnable under Miri.
+//!
+//!OS semaphores cannot be executed by Miri, hence only modules without FFI are covered,
+//!run with `cargo +nightly miri test --all-features --test miri`
+
+use semka::atomic_counting::Sem;
+use semka::spin;
+
+//Miri is slow, so number of iterations is reduced, while still exercising interleavings
+const ITERATIONS: u32 = if cfg!(miri) { 20 } else { 1000 };
+const THREADS: u32 = 3;
+
+#[test]
+fn should_take_each_permit_once_with_try_wait() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    let sem = Sem::new(THREADS * ITERATIONS / 2);
+    let acquired = AtomicU32::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    if sem.try_wait() {
+                        acquired.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(acquired.load(Ordering::Relaxed), THREADS * ITERATIONS / 2);
+    assert_eq!(sem.value(), 0);
+}
+
+#[test]
+fn should_wait_for_signal_across_threads() {
+    let sem = Sem::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    sem.wait();
+                }
+            });
+        }
+
+        for _ in 0..ITERATIONS {
+            sem.signal_many(THREADS - 1);
+            sem.signal();
+        }
+    });
+
+    assert_eq!(sem.value(), 0);
+}
+
+#[test]
+fn should_ping_pong_between_threads() {
+    let ping = Sem::new(0);
+    let pong = Sem::new(0);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..ITERATIONS {
+                ping.wait_with_strategy(&spin::Backoff);
+                pong.signal();
+            }
+        });
+
+        for _ in 0..ITERATIONS {
+            ping.signal();
+            pong.wait_with_strategy(&spin::PureSpin);
+        }
+    });
+
+    assert!(!ping.try_wait());
+    assert!(!pong.try_wait());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn should_exclude_holders_with_timeout() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time;
+
+    let sem = Sem::new(1);
+    let is_locked = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    if sem.wait_timeout(time::Duration::from_secs(60)) {
+                        assert!(!is_locked.swap(true, Ordering::Relaxed));
+                        assert!(is_locked.swap(false, Ordering::Relaxed));
+                        sem.signal();
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(sem.value(), 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn should_park_until_signal() {
+    let sem = semka::parking::Sem::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    sem.wait();
+                }
+            });
+        }
+
+        for _ in 0..THREADS * ITERATIONS {
+            sem.signal();
+        }
+    });
+
+    assert_eq!(sem.value(), 0);
+    assert!(!sem.try_wait());
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "std")]
+
+use semka::{MockSem, Semaphore};
+use std::time;
+
+#[test]
+fn should_count_permits() {
+    let sem = MockSem::new(1);
+
+    assert!(sem.try_wait());
+    assert!(!sem.try_wait());
+    sem.signal();
+    sem.signal();
+    assert_eq!(sem.value(), 2);
+    sem.wait();
+    assert!(sem.wait_timeout(time::Duration::from_millis(1)));
+    assert_eq!(sem.value(), 0);
+    assert!(!sem.wait_timeout(time::Duration::from_millis(1)));
+}
+
+#[test]
+fn should_force_timeout() {
+    let sem = MockSem::new(1);
+
+    sem.set_force_timeout(true);
+    assert!(!sem.wait_timeout(time::Duration::from_secs(5)));
+    assert_eq!(sem.value(), 1);
+
+    sem.set_force_timeout(false);
+    assert!(sem.wait_timeout(time::Duration::from_secs(5)));
+}
+
+#[test]
+fn should_delay_wait() {
+    let sem = MockSem::new(1);
+    sem.set_delay(time::Duration::from_millis(50));
+
+    let before = time::Instant::now();
+    assert!(sem.try_wait());
+    assert!(before.elapsed() >= time::Duration::from_millis(50));
+}
+
+#[test]
+fn should_wake_waiter() {
+    let sem = MockSem::new(0);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(time::Duration::from_millis(50));
+            sem.signal();
+        });
+
+        sem.wait();
+    });
+
+    assert_eq!(sem.value(), 0);
+}
@@ -0,0 +1,59 @@
+//!Verifies that core semaphore API performs no heap allocation.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use core::time;
+
+struct CountingAlloc;
+
+std::thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|count| count.get())
+}
+
+#[test]
+fn should_not_allocate() {
+    let before = allocations();
+
+    let sem = semka::Sem::new(1).unwrap();
+    assert!(sem.try_wait());
+    sem.signal();
+    sem.wait();
+    assert!(!sem.wait_timeout(time::Duration::from_millis(1)));
+    sem.signal();
+    drop(sem.acquire());
+    drop(sem);
+
+    #[cfg(feature = "binary")]
+    {
+        let binary = semka::BinarySem::new().unwrap();
+        drop(binary.lock());
+    }
+
+    static SEM: semka::ConstSem = semka::ConstSem::new(1);
+    assert!(SEM.try_wait());
+    SEM.signal();
+
+    let atomic = semka::atomic_counting::Sem::new(1);
+    atomic.wait();
+    atomic.signal();
+
+    assert_eq!(allocations(), before);
+}
@@ -0,0 +1,55 @@
+use semka::Notify;
+use std::time;
+
+#[test]
+fn should_collapse_notifications() {
+    let notify = Notify::new().unwrap();
+    assert!(!notify.try_wait_for_notification());
+
+    notify.notify_one();
+    notify.notify_one();
+    notify.notify_one();
+
+    notify.wait_for_notification();
+    assert!(!notify.try_wait_for_notification());
+
+    notify.notify_one();
+    assert!(notify.try_wait_for_notification());
+    assert!(!notify.try_wait_for_notification());
+}
+
+#[test]
+fn should_wake_waiting_thread() {
+    let notify = Notify::new().unwrap();
+
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| notify.wait_for_notification());
+
+        std::thread::sleep(time::Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+        notify.notify_one();
+        waiter.join().unwrap();
+    });
+
+    assert!(!notify.try_wait_for_notification());
+}
+
+#[test]
+fn should_not_accumulate_concurrent_notifications() {
+    const THREADS: usize = 8;
+
+    let notify = Notify::new().unwrap();
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..1000 {
+                    notify.notify_one();
+                }
+            });
+        }
+    });
+
+    assert!(notify.try_wait_for_notification());
+    assert!(!notify.try_wait_for_notification());
+}
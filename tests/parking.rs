@@ -0,0 +1,92 @@
+#![cfg(feature = "std")]
+
+use semka::parking::Sem;
+use std::time;
+
+#[test]
+fn should_count_permits() {
+    static SEM: Sem = Sem::new(1);
+
+    assert!(SEM.try_wait());
+    assert!(!SEM.try_wait());
+
+    SEM.signal();
+    SEM.signal_many(2);
+    assert_eq!(SEM.value(), 3);
+    SEM.wait();
+    assert!(SEM.try_wait());
+    assert!(SEM.try_wait());
+    assert!(!SEM.try_wait());
+}
+
+#[test]
+fn should_ignore_spurious_unpark() {
+    let sem = Sem::new(0);
+
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| sem.wait());
+
+        std::thread::sleep(time::Duration::from_millis(50));
+        waiter.thread().unpark();
+        std::thread::sleep(time::Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+
+        sem.signal();
+    });
+
+    assert_eq!(sem.value(), 0);
+}
+
+#[test]
+fn should_pass_items_from_producers_to_consumers() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const THREADS: u32 = 4;
+    const ITEMS: u32 = 10_000;
+
+    let items = Sem::new(0);
+    let consumed = AtomicU32::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITEMS {
+                    items.wait();
+                    consumed.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for idx in 0..ITEMS {
+                    if idx % 10 == 0 {
+                        std::thread::yield_now();
+                    }
+                    items.signal();
+                }
+            });
+        }
+    });
+
+    assert_eq!(consumed.load(Ordering::Relaxed), THREADS * ITEMS);
+    assert_eq!(items.value(), 0);
+}
+
+#[test]
+fn should_wake_multiple_waiters() {
+    const THREADS: u32 = 4;
+
+    let sem = Sem::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| sem.wait());
+        }
+
+        std::thread::sleep(time::Duration::from_millis(50));
+        sem.signal_many(THREADS);
+    });
+
+    assert_eq!(sem.value(), 0);
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "counting")]
+
+use semka::PermitPool;
+
+#[test]
+fn should_track_outstanding_permits() {
+    let pool = PermitPool::new(2).unwrap();
+    assert_eq!(pool.capacity(), 2);
+    assert_eq!(pool.outstanding(), 0);
+    assert_eq!(pool.available(), 2);
+
+    let first = pool.acquire();
+    let second = pool.try_acquire().unwrap();
+    assert_eq!(pool.outstanding(), 2);
+    assert_eq!(pool.available(), 0);
+    assert!(pool.try_acquire().is_none());
+    assert_eq!(pool.outstanding(), 2);
+
+    drop(first);
+    assert_eq!(pool.outstanding(), 1);
+    assert_eq!(pool.available(), 1);
+    drop(second);
+    assert_eq!(pool.outstanding(), 0);
+    assert_eq!(pool.available(), 2);
+}
+
+#[test]
+fn should_return_permit_on_panic() {
+    let pool = PermitPool::new(1).unwrap();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _permit = pool.acquire();
+        assert_eq!(pool.outstanding(), 1);
+        panic!("task failed");
+    }));
+    assert!(result.is_err());
+
+    assert_eq!(pool.outstanding(), 0);
+    assert_eq!(pool.available(), 1);
+    assert!(pool.try_acquire().is_some());
+}
+
+#[test]
+fn should_balance_counters_across_threads() {
+    const THREADS: u32 = 8;
+    const ITERATIONS: u32 = 1000;
+    const CAPACITY: u32 = 3;
+
+    let pool = PermitPool::new(CAPACITY).unwrap();
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    let _permit = pool.acquire();
+                    assert!(pool.outstanding() <= CAPACITY);
+                }
+            });
+        }
+    });
+
+    assert_eq!(pool.outstanding(), 0);
+    assert_eq!(pool.available(), CAPACITY);
+}
@@ -0,0 +1,107 @@
+#![cfg(all(feature = "binary", feature = "counting"))]
+
+use semka::RwSem;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time;
+
+#[test]
+fn should_share_read_and_exclude_write() {
+    let lock = RwSem::new(2).unwrap();
+    assert_eq!(lock.max_readers(), 2);
+    assert!(RwSem::new(0).is_none());
+    assert!(RwSem::new(RwSem::MAX_READERS + 1).is_none());
+    assert!(RwSem::new(semka::Sem::MAX).is_none());
+
+    let first = lock.read();
+    let second = lock.try_read().unwrap();
+    assert!(lock.try_read().is_none());
+    assert!(lock.try_write().is_none());
+
+    drop(first);
+    assert!(lock.try_write().is_none());
+    drop(second);
+
+    let write = lock.try_write().unwrap();
+    assert!(lock.try_read().is_none());
+    assert!(lock.try_write().is_none());
+    drop(write);
+
+    assert!(lock.try_read().is_some());
+}
+
+#[test]
+fn should_allow_concurrent_readers_and_exclusive_writers() {
+    const READERS: u32 = 4;
+    const WRITERS: u32 = 2;
+    const ITERATIONS: u32 = 200;
+
+    let lock = RwSem::new(READERS).unwrap();
+    let active_readers = AtomicU32::new(0);
+    let max_active_readers = AtomicU32::new(0);
+    let active_writers = AtomicU32::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..READERS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    let _guard = lock.read();
+                    let active = active_readers.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_active_readers.fetch_max(active, Ordering::SeqCst);
+                    assert_eq!(active_writers.load(Ordering::SeqCst), 0);
+                    std::thread::sleep(time::Duration::from_micros(50));
+                    active_readers.fetch_sub(1, Ordering::SeqCst);
+                }
+            });
+        }
+
+        for _ in 0..WRITERS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS / 4 {
+                    let _guard = lock.write();
+                    assert_eq!(active_writers.fetch_add(1, Ordering::SeqCst), 0);
+                    assert_eq!(active_readers.load(Ordering::SeqCst), 0);
+                    std::thread::sleep(time::Duration::from_micros(50));
+                    active_writers.fetch_sub(1, Ordering::SeqCst);
+                }
+            });
+        }
+    });
+
+    assert!(max_active_readers.load(Ordering::SeqCst) <= READERS);
+    assert!(lock.try_write().is_some());
+}
+
+#[test]
+fn should_write_with_max_readers() {
+    let lock = RwSem::new(RwSem::MAX_READERS).unwrap();
+
+    let read = lock.read();
+    assert!(lock.try_write().is_none());
+    drop(read);
+
+    drop(lock.write());
+    assert!(lock.try_write().is_some());
+}
+
+#[test]
+fn should_try_read_concurrently_without_writer() {
+    const READERS: u32 = 4;
+    const ITERATIONS: u32 = 10_000;
+
+    let lock = RwSem::new(READERS).unwrap();
+    let barrier = std::sync::Barrier::new(READERS as usize);
+
+    std::thread::scope(|scope| {
+        for _ in 0..READERS {
+            scope.spawn(|| {
+                barrier.wait();
+                for _ in 0..ITERATIONS {
+                    //Other readers passing through turnstile must not make it fail
+                    assert!(lock.try_read().is_some());
+                }
+            });
+        }
+    });
+
+    assert!(lock.try_write().is_some());
+}
@@ -76,3 +76,1210 @@ fn should_timeout_on_wait() {
     println!("duration={:?}", duration);
     assert!(duration.as_millis() > 2000 && duration.as_millis() < 3000);
 }
+
+#[test]
+fn should_release_permit_on_guard_drop() {
+    let sem = Sem::new(1).unwrap();
+
+    {
+        let guard = sem.acquire();
+        assert!(core::ptr::eq(guard.sem(), &sem));
+        assert!(sem.try_acquire().is_none());
+    }
+
+    let guard = sem.try_acquire();
+    assert!(guard.is_some());
+    assert!(!sem.try_wait());
+    drop(guard);
+    assert!(sem.try_wait());
+}
+
+#[test]
+fn should_iterate_permits() {
+    let sem = Sem::new(1).unwrap();
+
+    let mut count = 0;
+    for _permit in sem.permits().take(5) {
+        assert!(!sem.try_wait());
+        count += 1;
+    }
+
+    assert_eq!(count, 5);
+    assert!(sem.try_wait());
+    assert!(!sem.try_wait());
+}
+
+#[test]
+fn should_signal_many() {
+    let sem = Sem::new(0).unwrap();
+
+    sem.signal_many(0);
+    assert!(!sem.try_wait());
+
+    sem.signal_many(3);
+    assert!(sem.try_wait());
+    assert!(sem.try_wait());
+    assert!(sem.try_wait());
+    assert!(!sem.try_wait());
+}
+
+#[cfg(any(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))), target_os = "fuchsia"))]
+#[test]
+fn should_increase_value_by_exact_count_on_signal_many() {
+    let sem = Sem::new(1).unwrap();
+
+    for count in [0u32, 1, 7, 64].iter().copied() {
+        let before = sem.value();
+        sem.signal_many(count);
+        assert_eq!(sem.value(), before + count);
+    }
+}
+
+#[test]
+fn should_signal_checked() {
+    let sem = Sem::new(0).unwrap();
+
+    assert!(sem.signal_checked().is_ok());
+    assert!(sem.try_wait());
+    assert!(!sem.try_wait());
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos")))]
+#[test]
+fn should_report_overflow_on_signal_checked() {
+    let sem = Sem::new(Sem::MAX).unwrap();
+
+    assert_eq!(sem.signal_checked(), Err(semka::SemError::Overflow));
+    assert!(sem.try_wait());
+    assert!(sem.signal_checked().is_ok());
+}
+
+#[test]
+fn should_wait_any() {
+    let first = Sem::new(0).unwrap();
+    let second = Sem::new(0).unwrap();
+
+    second.signal();
+    assert_eq!(Sem::wait_any(&[&first, &second]), 1);
+    assert!(!second.try_wait());
+
+    first.signal();
+    second.signal();
+    assert_eq!(Sem::wait_any(&[&first, &second]), 0);
+    assert_eq!(Sem::wait_any(&[&first, &second]), 1);
+    assert!(!first.try_wait());
+    assert!(!second.try_wait());
+}
+
+#[test]
+fn should_wait_any_until_signaled() {
+    let first = Sem::new(0).unwrap();
+    let second = Sem::new(0).unwrap();
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(time::Duration::from_millis(50));
+            second.signal();
+        });
+
+        assert_eq!(Sem::wait_any(&[&first, &second]), 1);
+    });
+}
+
+#[test]
+fn should_wait_all() {
+    let first = Sem::new(2).unwrap();
+    let second = Sem::new(1).unwrap();
+
+    Sem::wait_all(&[&first, &second]);
+    assert!(!second.try_wait());
+    assert!(first.try_wait());
+    assert!(!first.try_wait());
+}
+
+#[test]
+fn should_wait_all_until_signaled() {
+    let first = Sem::new(1).unwrap();
+    let second = Sem::new(0).unwrap();
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(time::Duration::from_millis(50));
+            second.signal();
+        });
+
+        Sem::wait_all(&[&first, &second]);
+    });
+
+    assert!(!first.try_wait());
+    assert!(!second.try_wait());
+}
+
+#[test]
+#[should_panic(expected = "wait_all() requires distinct semaphores")]
+fn should_reject_duplicate_sem_in_wait_all() {
+    let first = Sem::new(1).unwrap();
+    let second = Sem::new(1).unwrap();
+
+    Sem::wait_all(&[&first, &second, &first]);
+}
+
+#[test]
+fn should_wait_checked() {
+    let sem = Sem::new(1).unwrap();
+
+    assert!(sem.wait_checked().is_ok());
+    assert!(!sem.try_wait());
+}
+
+#[test]
+fn should_try_wait_checked() {
+    let sem = Sem::new(1).unwrap();
+
+    assert_eq!(sem.try_wait_checked(), Ok(true));
+    assert_eq!(sem.try_wait_checked(), Ok(false));
+    sem.signal();
+    assert_eq!(sem.try_wait_checked(), Ok(true));
+}
+
+#[test]
+fn should_report_uninitialized_in_checked_operations() {
+    use semka::SemError;
+
+    let sem = unsafe {
+        Sem::new_uninit()
+    };
+
+    assert_eq!(sem.wait_checked(), Err(SemError::Uninitialized));
+    assert_eq!(sem.try_wait_checked(), Err(SemError::Uninitialized));
+    assert_eq!(sem.signal_checked(), Err(SemError::Uninitialized));
+    assert_eq!(SemError::Uninitialized.to_string(), "Semaphore is not initialized");
+}
+
+#[cfg(feature = "binary")]
+#[test]
+fn should_lock_binary_sem() {
+    let sem = semka::BinarySem::new().unwrap();
+
+    assert!(sem.is_init());
+    assert!(!sem.init());
+
+    let lock = sem.lock();
+    assert!(sem.try_lock().is_none());
+    drop(lock);
+
+    let lock = sem.try_lock();
+    assert!(lock.is_some());
+    assert!(sem.try_lock().is_none());
+}
+
+#[cfg(feature = "binary")]
+#[test]
+fn should_relock_binary_sem_after_drop() {
+    let sem = semka::BinarySem::new().unwrap();
+
+    for _ in 0..3 {
+        let lock = sem.try_lock();
+        assert!(lock.is_some());
+        assert!(sem.try_lock().is_none());
+    }
+
+    drop(sem.lock());
+    drop(sem.lock());
+    assert!(sem.try_lock().is_some());
+}
+
+#[cfg(feature = "binary")]
+#[test]
+fn should_exclude_binary_sem_holders() {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    const THREADS: usize = 4;
+    const ITERATIONS: usize = 100;
+
+    let sem = semka::BinarySem::new().unwrap();
+    let is_locked = AtomicBool::new(false);
+    let count = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    let _lock = sem.lock();
+                    assert!(!is_locked.swap(true, Ordering::SeqCst));
+                    count.fetch_add(1, Ordering::SeqCst);
+                    std::thread::yield_now();
+                    assert!(is_locked.swap(false, Ordering::SeqCst));
+                }
+            });
+        }
+    });
+
+    assert_eq!(count.load(Ordering::SeqCst), THREADS * ITERATIONS);
+    assert!(sem.try_lock().is_some());
+}
+
+#[cfg(feature = "binary")]
+#[test]
+fn should_lock_binary_sem_within_timeout() {
+    let sem = semka::BinarySem::new().unwrap();
+
+    let lock = sem.try_lock_for(time::Duration::from_millis(10));
+    assert!(lock.is_some());
+
+    let before = time::Instant::now();
+    assert!(sem.try_lock_for(time::Duration::from_millis(100)).is_none());
+    assert!(before.elapsed() >= time::Duration::from_millis(90));
+
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            std::thread::sleep(time::Duration::from_millis(50));
+            drop(lock);
+        });
+
+        assert!(sem.try_lock_for(time::Duration::from_secs(5)).is_some());
+    });
+}
+
+#[cfg(all(feature = "binary", feature = "std"))]
+#[test]
+fn should_poison_binary_sem_on_panic() {
+    let sem = semka::BinarySem::new().unwrap();
+
+    assert!(!sem.is_poisoned());
+    assert!(!sem.lock().is_poisoned());
+
+    let result = std::thread::scope(|scope| {
+        scope.spawn(|| {
+            let _lock = sem.lock();
+            panic!("Panic while holding lock");
+        }).join()
+    });
+    assert!(result.is_err());
+
+    assert!(sem.is_poisoned());
+    let lock = sem.try_lock().unwrap();
+    assert!(lock.is_poisoned());
+    drop(lock);
+    assert!(sem.is_poisoned());
+
+    sem.clear_poison();
+    assert!(!sem.is_poisoned());
+    assert!(!sem.lock().is_poisoned());
+}
+
+#[test]
+fn should_wait_counting_interrupts() {
+    let sem = Sem::new(1).unwrap();
+
+    assert_eq!(sem.wait_counting_interrupts(), 0);
+    assert!(!sem.try_wait());
+}
+
+#[test]
+fn should_leak_without_close() {
+    let sem = Sem::new(1).unwrap();
+    assert!(sem.try_wait());
+    sem.leak();
+}
+
+#[cfg(windows)]
+#[test]
+fn should_wake_fair_waiters_in_order() {
+    use std::sync::Mutex;
+
+    const WAITERS: usize = 4;
+
+    let sem = Sem::new_fair(0).unwrap();
+    assert!(sem.is_fair());
+    let order = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for idx in 0..WAITERS {
+            let sem = &sem;
+            let order = &order;
+            scope.spawn(move || {
+                sem.wait();
+                order.lock().unwrap().push(idx);
+            });
+            //Give waiter time to get in queue
+            std::thread::sleep(time::Duration::from_millis(50));
+        }
+
+        assert!(!sem.try_wait());
+        for _ in 0..WAITERS {
+            sem.signal();
+            std::thread::sleep(time::Duration::from_millis(50));
+        }
+    });
+
+    assert_eq!(*order.lock().unwrap(), (0..WAITERS).collect::<Vec<_>>());
+}
+
+#[test]
+fn should_use_sem_via_trait() {
+    use semka::Semaphore;
+
+    fn drain<S: Semaphore>(sem: &S) -> usize {
+        let mut count = 0;
+        while sem.try_wait() {
+            count += 1;
+        }
+        count
+    }
+
+    let sem = Sem::new(0).unwrap();
+    Semaphore::signal(&sem);
+    Semaphore::signal(&sem);
+    assert_eq!(drain(&sem), 2);
+    assert!(!Semaphore::wait_timeout(&sem, time::Duration::from_millis(1)));
+    Semaphore::signal(&sem);
+    Semaphore::wait(&sem);
+    assert_eq!(drain(&sem), 0);
+}
+
+#[test]
+fn should_reject_init_above_max() {
+    assert!(Sem::new(u32::MAX).is_none());
+    assert!(Sem::new(Sem::MAX + 1).is_none());
+
+    let sem = unsafe {
+        Sem::new_uninit()
+    };
+    assert!(!sem.init(u32::MAX));
+    assert!(!sem.is_init());
+    assert!(sem.init(Sem::MAX));
+    assert!(sem.try_wait());
+}
+
+#[cfg(unix)]
+#[test]
+fn should_signal_from_signal_handler() {
+    static SEM: Sem = unsafe {
+        Sem::new_uninit()
+    };
+
+    extern "C" fn on_signal(_: libc::c_int) {
+        SEM.signal_from_handler();
+    }
+
+    assert!(SEM.init(0));
+
+    //Handler is process-wide, so previous one is restored to not affect other tests
+    let mut old_action: libc::sigaction = unsafe {
+        core::mem::zeroed()
+    };
+    unsafe {
+        let handler: extern "C" fn(libc::c_int) = on_signal;
+        let mut action: libc::sigaction = core::mem::zeroed();
+        action.sa_sigaction = handler as libc::sighandler_t;
+        assert_eq!(libc::sigaction(libc::SIGUSR2, &action, &mut old_action), 0);
+        assert_eq!(libc::raise(libc::SIGUSR2), 0);
+    }
+
+    let signaled = SEM.wait_timeout(time::Duration::from_secs(1));
+    unsafe {
+        assert_eq!(libc::sigaction(libc::SIGUSR2, &old_action, core::ptr::null_mut()), 0);
+    }
+
+    assert!(signaled);
+    assert!(!SEM.try_wait());
+}
+
+#[test]
+fn should_cycle_init_and_close() {
+    let sem = unsafe {
+        Sem::new_uninit()
+    };
+
+    unsafe {
+        sem.close();
+    }
+    assert!(!sem.is_init());
+
+    for init in 0..3 {
+        assert!(sem.init(init));
+        assert!(sem.is_init());
+        assert!(!sem.init(init));
+
+        for _ in 0..init {
+            assert!(sem.try_wait());
+        }
+        assert!(!sem.try_wait());
+
+        unsafe {
+            sem.close();
+        }
+        assert!(!sem.is_init());
+        unsafe {
+            sem.close();
+        }
+        assert!(!sem.is_init());
+    }
+}
+
+#[test]
+fn should_timeout_on_sub_millisecond_wait() {
+    let sem = Sem::new(0).unwrap();
+
+    let before = time::Instant::now();
+    assert!(!sem.wait_timeout(time::Duration::from_micros(500)));
+    let duration = before.elapsed();
+    assert!(duration >= time::Duration::from_micros(500), "duration={:?}", duration);
+
+    sem.signal();
+    assert!(sem.wait_timeout(time::Duration::from_micros(500)));
+}
+
+#[test]
+fn should_init_const_sem_on_first_use() {
+    use semka::ConstSem;
+
+    static SEM: ConstSem = ConstSem::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                SEM.signal();
+            });
+        }
+    });
+
+    assert!(SEM.is_init());
+    for _ in 0..8 {
+        assert!(SEM.try_wait());
+    }
+    assert!(!SEM.get().try_wait());
+}
+
+#[test]
+fn should_get_or_init_once_concurrently() {
+    const THREADS: usize = 16;
+
+    let sem = unsafe {
+        Sem::new_uninit()
+    };
+    //Failed init leaves semaphore uninitialized, to be retried on next call
+    assert!(!sem.init(u32::MAX));
+    assert!(!sem.is_init());
+
+    let barrier = std::sync::Barrier::new(THREADS);
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                barrier.wait();
+                //Losing threads must not re-initialize semaphore and reset taken permits
+                assert!(sem.get_or_init(THREADS as u32).try_wait());
+            });
+        }
+    });
+
+    assert!(sem.is_init());
+    assert!(!sem.get_or_init(THREADS as u32).try_wait());
+}
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))))]
+#[test]
+fn should_wait_until_empty() {
+    const PERMITS: u32 = 16;
+
+    let sem = Sem::new(0).unwrap();
+    sem.signal_many(PERMITS);
+    assert_eq!(sem.value(), PERMITS);
+
+    std::thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                while sem.try_wait() {
+                    std::thread::sleep(time::Duration::from_millis(1));
+                }
+            });
+        }
+
+        sem.wait_until_empty();
+        assert_eq!(sem.value(), 0);
+    });
+}
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))))]
+#[test]
+fn should_wait_for_count_without_consuming() {
+    const PERMITS: u32 = 4;
+
+    let sem = Sem::new(1).unwrap();
+    sem.wait_for_count(1);
+    sem.wait_for_count(0);
+    assert_eq!(sem.value(), 1);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 1..PERMITS {
+                std::thread::sleep(time::Duration::from_millis(5));
+                sem.signal();
+            }
+        });
+
+        sem.wait_for_count(PERMITS);
+    });
+
+    assert_eq!(sem.value(), PERMITS);
+}
+
+#[test]
+fn should_apply_timeout_policy() {
+    use semka::{SemError, TimeoutPolicy};
+
+    let max = time::Duration::new(u64::MAX, 999_999_999);
+    let sem = Sem::new(0).unwrap();
+
+    assert_eq!(TimeoutPolicy::default(), TimeoutPolicy::Saturate);
+    assert_eq!(sem.wait_timeout_with_policy(time::Duration::from_millis(1), TimeoutPolicy::Error), Ok(false));
+
+    sem.signal();
+    assert_eq!(sem.wait_timeout_with_policy(max, TimeoutPolicy::Saturate), Ok(true));
+    sem.signal();
+    assert_eq!(sem.wait_timeout_with_policy(max, TimeoutPolicy::Infinite), Ok(true));
+
+    sem.signal();
+    //Windows can await any timeout
+    if cfg!(windows) {
+        assert_eq!(sem.wait_timeout_with_policy(max, TimeoutPolicy::Error), Ok(true));
+    } else {
+        assert_eq!(sem.wait_timeout_with_policy(max, TimeoutPolicy::Error), Err(SemError::TimeoutOverflow));
+        assert!(sem.try_wait());
+    }
+    assert!(!sem.try_wait());
+
+    sem.signal();
+    assert!(sem.wait_timeout(max));
+}
+
+#[cfg(feature = "counting")]
+#[test]
+fn should_limit_concurrency() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const LIMIT: u32 = 2;
+
+    let limiter = semka::ConcurrencyLimiter::new(LIMIT).unwrap();
+    let running = AtomicU32::new(0);
+    let max_running = AtomicU32::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                limiter.run(|| {
+                    let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(current, Ordering::SeqCst);
+                    std::thread::sleep(time::Duration::from_millis(10));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                });
+            });
+        }
+    });
+
+    assert!(max_running.load(Ordering::SeqCst) <= LIMIT);
+    assert_eq!(limiter.try_run(|| 1), Some(1));
+}
+
+#[cfg(feature = "counting")]
+#[test]
+fn should_release_limiter_permit_on_panic() {
+    let limiter = semka::ConcurrencyLimiter::new(1).unwrap();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        limiter.run(|| panic!("task failure"))
+    }));
+    assert!(result.is_err());
+
+    assert!(limiter.sem().try_wait());
+    assert!(!limiter.sem().try_wait());
+}
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))))]
+#[test]
+fn should_wait_masked_restoring_signal_mask() {
+    fn current_mask() -> libc::sigset_t {
+        unsafe {
+            let mut mask = core::mem::zeroed();
+            assert_eq!(libc::pthread_sigmask(libc::SIG_SETMASK, core::ptr::null(), &mut mask), 0);
+            mask
+        }
+    }
+
+    let sem = Sem::new(0).unwrap();
+    let before = current_mask();
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(time::Duration::from_millis(50));
+            sem.signal();
+        });
+
+        sem.wait_masked();
+    });
+
+    let after = current_mask();
+    for signal in 1..32 {
+        unsafe {
+            assert_eq!(libc::sigismember(&before, signal), libc::sigismember(&after, signal));
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn should_return_remaining_timeout() {
+    let sem = Sem::new(1).unwrap();
+    let timeout = time::Duration::from_secs(1);
+
+    let remaining = sem.wait_timeout_remaining(timeout).unwrap();
+    assert!(remaining <= timeout);
+    assert!(remaining > time::Duration::from_millis(900), "remaining={:?}", remaining);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(time::Duration::from_millis(200));
+            sem.signal();
+        });
+
+        let remaining = sem.wait_timeout_remaining(timeout).unwrap();
+        assert!(remaining < time::Duration::from_millis(850), "remaining={:?}", remaining);
+    });
+
+    assert_eq!(sem.wait_timeout_remaining(time::Duration::from_millis(10)), None);
+}
+
+#[cfg(target_os = "android")]
+#[test]
+fn should_wait_timeout_accurately_on_bionic() {
+    let sem = Sem::new(0).unwrap();
+
+    for timeout in [1u64, 10, 100, 500].iter().copied() {
+        let timeout = time::Duration::from_millis(timeout);
+        let before = time::Instant::now();
+        assert!(!sem.wait_timeout(timeout));
+        let elapsed = before.elapsed();
+        assert!(elapsed >= timeout, "timeout={:?} elapsed={:?}", timeout, elapsed);
+        assert!(elapsed < timeout + time::Duration::from_millis(200), "timeout={:?} elapsed={:?}", timeout, elapsed);
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+#[test]
+fn should_create_with_sync_policy() {
+    use semka::SyncPolicy;
+
+    assert_eq!(SyncPolicy::default(), SyncPolicy::Fifo);
+
+    for policy in [SyncPolicy::Fifo, SyncPolicy::Lifo, SyncPolicy::Prepost].iter().copied() {
+        let sem = Sem::new_with_policy(1, policy).unwrap();
+        assert!(sem.try_wait());
+        assert!(!sem.try_wait());
+        sem.signal();
+        assert!(sem.wait_timeout(time::Duration::from_millis(10)));
+    }
+}
+
+#[test]
+fn should_create_single_semaphore_on_concurrent_init() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const THREADS: u32 = 8;
+
+    for _ in 0..100 {
+        let sem = unsafe {
+            Sem::new_uninit()
+        };
+        let succeeded = AtomicU32::new(0);
+        let barrier = std::sync::Barrier::new(THREADS as usize);
+
+        std::thread::scope(|scope| {
+            for _ in 0..THREADS {
+                scope.spawn(|| {
+                    barrier.wait();
+                    if sem.init(1) {
+                        succeeded.fetch_add(1, Ordering::Relaxed);
+                    }
+                    //Losers must observe semaphore created by winner
+                    assert!(sem.is_init());
+                });
+            }
+        });
+
+        assert_eq!(succeeded.load(Ordering::Relaxed), 1);
+        assert!(sem.try_wait());
+        assert!(!sem.try_wait());
+    }
+}
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))))]
+//On Linux and Android, which CI runs, `waiters` is always `None`, hence only that is verified there.
+//Elsewhere number of waiters is only checked to be within bounds, as platform may report zero instead.
+#[test]
+fn should_report_waiters_if_supported() {
+    const WAITERS: u32 = 3;
+
+    let sem = Sem::new(0).unwrap();
+
+    std::thread::scope(|scope| {
+        for _ in 0..WAITERS {
+            scope.spawn(|| sem.wait());
+        }
+        //Give waiters time to park
+        std::thread::sleep(time::Duration::from_millis(50));
+
+        let waiters = sem.waiters();
+        if cfg!(any(target_os = "linux", target_os = "android")) {
+            assert_eq!(waiters, None);
+        } else {
+            assert!(waiters.unwrap() <= WAITERS, "waiters={:?}", waiters);
+        }
+
+        sem.signal_many(WAITERS);
+    });
+
+    if cfg!(any(target_os = "linux", target_os = "android")) {
+        assert_eq!(sem.waiters(), None);
+    } else {
+        assert_eq!(sem.waiters(), Some(0));
+    }
+}
+
+#[test]
+fn should_cancel_blocked_wait() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let sem = Sem::new(1).unwrap();
+    let cancel = AtomicBool::new(false);
+
+    assert!(sem.wait_cancellable(&cancel));
+
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| sem.wait_cancellable(&cancel));
+
+        std::thread::sleep(time::Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+        cancel.store(true, Ordering::Release);
+
+        assert!(!waiter.join().unwrap());
+    });
+
+    sem.signal();
+    assert!(!sem.wait_cancellable(&cancel));
+    assert!(sem.try_wait());
+}
+
+#[test]
+fn should_have_expected_layout() {
+    use core::mem;
+
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))))]
+    {
+        assert!(mem::size_of::<Sem>() > mem::size_of::<libc::sem_t>());
+        assert!(mem::align_of::<Sem>() >= mem::align_of::<libc::sem_t>());
+    }
+    #[cfg(any(windows, target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))]
+    assert!(mem::size_of::<Sem>() >= mem::size_of::<usize>());
+
+    assert_eq!(mem::align_of::<semka::atomic_counting::Sem>(), mem::align_of::<u32>());
+}
+
+#[test]
+fn should_observe_whether_wait_blocked() {
+    let sem = Sem::new(1).unwrap();
+
+    assert!(!sem.wait_observed());
+    assert!(!sem.try_wait());
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(time::Duration::from_millis(50));
+            sem.signal();
+        });
+
+        assert!(sem.wait_observed());
+    });
+
+    assert!(!sem.try_wait());
+}
+
+#[test]
+fn should_wait_adaptive_taking_single_permit() {
+    let sem = Sem::new(2).unwrap();
+
+    sem.wait_adaptive(100, 10);
+    sem.wait_adaptive(0, 0);
+    assert!(!sem.try_wait());
+
+    for (spin, yields) in [(0, 0), (100, 0), (0, 10), (100, 10), (u32::MAX, 0)].iter().copied() {
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                std::thread::sleep(time::Duration::from_millis(10));
+                sem.signal();
+            });
+
+            sem.wait_adaptive(spin, yields);
+        });
+        assert!(!sem.try_wait());
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn should_honor_sub_millisecond_timeout() {
+    let sem = Sem::new(0).unwrap();
+
+    for timeout in [50u64, 200, 500, 999_999].iter().copied() {
+        let timeout = time::Duration::from_nanos(timeout * 1_000);
+        for _ in 0..20 {
+            let before = time::Instant::now();
+            assert!(!sem.wait_timeout(timeout));
+            let elapsed = before.elapsed();
+            assert!(elapsed >= timeout, "timeout={:?} elapsed={:?}", timeout, elapsed);
+        }
+    }
+}
+
+//Checks are only performed in debug builds or with `strict`, otherwise it would be UB on uninitialized `sem_t`
+#[cfg(all(any(debug_assertions, feature = "strict"), unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))))]
+#[test]
+#[cfg_attr(not(feature = "lean_assert"), should_panic(expected = "wait() on uninitialized Sem"))]
+#[cfg_attr(feature = "lean_assert", should_panic(expected = "Semaphore misuse check failed"))]
+fn should_panic_on_uninitialized_wait() {
+    let sem = unsafe {
+        Sem::new_uninit()
+    };
+    sem.wait();
+}
+
+//Checks are only performed in debug builds or with `strict`, otherwise it would be UB on uninitialized `sem_t`
+#[cfg(all(any(debug_assertions, feature = "strict"), unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))))]
+#[test]
+#[cfg_attr(not(feature = "lean_assert"), should_panic(expected = "try_wait() on uninitialized Sem"))]
+#[cfg_attr(feature = "lean_assert", should_panic(expected = "Semaphore misuse check failed"))]
+fn should_panic_on_uninitialized_try_wait() {
+    let sem = unsafe {
+        Sem::new_uninit()
+    };
+    sem.try_wait();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn should_acquire_with_remaining_timeout() {
+    let sem = Sem::new(1).unwrap();
+    let timeout = time::Duration::from_secs(1);
+
+    let (guard, remaining) = sem.try_acquire_for_remaining(timeout).unwrap();
+    assert!(remaining <= timeout);
+    assert!(remaining > time::Duration::from_millis(900), "remaining={:?}", remaining);
+
+    assert!(sem.try_acquire_for_remaining(time::Duration::from_millis(10)).is_err());
+    drop(guard);
+
+    let (_guard, _) = sem.try_acquire_for_remaining(time::Duration::from_millis(10)).unwrap();
+    assert!(!sem.try_wait());
+}
+
+#[cfg(any(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))), target_os = "fuchsia"))]
+#[test]
+fn should_reject_out_of_range_init_value() {
+    use semka::SemError;
+
+    assert!(Sem::new(u32::MAX).is_none());
+
+    let sem = unsafe {
+        Sem::new_uninit()
+    };
+
+    assert_eq!(sem.init_checked(u32::MAX), Err(SemError::Overflow));
+    assert_eq!(sem.init_checked(Sem::MAX + 1), Err(SemError::Overflow));
+    assert!(!sem.is_init());
+
+    assert_eq!(sem.init_checked(Sem::MAX), Ok(()));
+    assert_eq!(sem.value(), Sem::MAX);
+    assert_eq!(sem.init_checked(0), Err(SemError::AlreadyInitialized));
+}
+
+//Covers retry after `init` rejected out of range value, which leaves semaphore uninitialized.
+//Out of range value is rejected before reaching OS (e.g. `sem_init`), and with it being the only
+//documented cause of `sem_init` failure for process-private semaphore, its failure cannot be forced in test.
+#[test]
+fn should_init_after_rejected_init() {
+    let sem = unsafe {
+        Sem::new_uninit()
+    };
+
+    assert!(!sem.init(Sem::MAX + 1));
+    assert!(!sem.is_init());
+    assert!(!sem.init(u32::MAX));
+    assert!(!sem.is_init());
+
+    assert!(sem.init(1));
+    assert!(sem.is_init());
+    assert!(sem.try_wait());
+    assert!(!sem.try_wait());
+    sem.signal();
+    assert!(sem.wait_timeout(time::Duration::from_millis(10)));
+}
+
+#[test]
+fn should_wait_timeout_with_unnormalized_durations() {
+    let sem = Sem::new(0).unwrap();
+
+    let durations = [
+        time::Duration::new(0, 2_000_000_000),
+        time::Duration::new(1, 1_999_999_999),
+        time::Duration::from_nanos(2_500_000_000),
+        time::Duration::from_millis(1_500),
+        time::Duration::new(u64::MAX, 999_999_999),
+    ];
+
+    for duration in durations.iter().copied() {
+        sem.signal();
+        assert!(sem.wait_timeout(duration), "duration={:?}", duration);
+    }
+
+    let before = time::Instant::now();
+    assert!(!sem.wait_timeout(time::Duration::new(0, 50_000_000)));
+    assert!(before.elapsed() >= time::Duration::from_millis(50));
+}
+
+#[test]
+fn should_return_wait_outcome() {
+    use semka::WaitOutcome;
+
+    let sem = Sem::new(1).unwrap();
+
+    let outcome = sem.wait_timeout_outcome(time::Duration::from_millis(10));
+    assert_eq!(outcome, WaitOutcome::Acquired);
+    assert!(outcome.is_acquired());
+
+    let outcome = sem.wait_timeout_outcome(time::Duration::from_millis(10));
+    assert_eq!(outcome, WaitOutcome::TimedOut);
+    assert!(outcome.is_timed_out());
+}
+
+#[cfg(windows)]
+#[test]
+fn should_signal_through_duplicate() {
+    let sem = Sem::new(0).unwrap();
+    let duplicate = sem.duplicate().unwrap();
+    assert_eq!(duplicate.max(), sem.max());
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| duplicate.signal());
+    });
+    assert!(sem.wait_timeout(time::Duration::from_secs(1)));
+
+    drop(sem);
+    duplicate.signal();
+    assert!(duplicate.try_wait());
+
+    let uninit = unsafe {
+        Sem::new_uninit()
+    };
+    assert!(uninit.duplicate().is_none());
+}
+
+#[cfg(not(windows))]
+#[test]
+fn should_not_duplicate_unnamed_sem() {
+    let sem = Sem::new(0).unwrap();
+    assert!(sem.duplicate().is_none());
+}
+
+#[test]
+fn should_report_timeout_support() {
+    use semka::Semaphore;
+
+    fn wait_briefly<S: Semaphore>(sem: &S) -> bool {
+        match S::SUPPORTS_TIMEOUT {
+            true => sem.wait_timeout(S::TIMEOUT_RESOLUTION),
+            false => sem.try_wait(),
+        }
+    }
+
+    assert_eq!(<Sem as Semaphore>::SUPPORTS_TIMEOUT, Sem::SUPPORTS_TIMEOUT);
+    assert!(Sem::TIMEOUT_RESOLUTION > time::Duration::from_secs(0));
+    #[cfg(windows)]
+    assert_eq!(Sem::TIMEOUT_RESOLUTION, time::Duration::from_millis(1));
+
+    let sem = Sem::new(1).unwrap();
+    assert!(wait_briefly(&sem));
+    assert!(!wait_briefly(&sem));
+
+    assert_eq!(semka::atomic_counting::Sem::SUPPORTS_TIMEOUT, cfg!(feature = "std"));
+    #[cfg(feature = "futex")]
+    const { assert!(!semka::futex::Sem::SUPPORTS_TIMEOUT) };
+}
+
+#[test]
+fn should_round_trip_count_through_sem_t() {
+    let sem = Sem::new(3).unwrap();
+    sem.signal_many(5);
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos")))]
+    assert_eq!(sem.value(), 8);
+
+    for _ in 0..8 {
+        assert!(sem.try_wait());
+    }
+    assert!(!sem.try_wait());
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos")))]
+    assert_eq!(sem.value(), 0);
+}
+
+#[cfg(windows)]
+#[test]
+fn should_wait_checked_in_fair_mode() {
+    let sem = Sem::new_fair(1).unwrap();
+
+    assert_eq!(sem.wait_checked(), Ok(()));
+    assert!(!sem.try_wait());
+
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| sem.wait_checked());
+        std::thread::sleep(time::Duration::from_millis(10));
+        sem.signal();
+        assert_eq!(waiter.join().unwrap(), Ok(()));
+    });
+
+    sem.signal();
+    assert!(sem.wait_timeout(time::Duration::from_millis(10)));
+}
+
+#[cfg(feature = "counting")]
+#[test]
+fn should_grow_and_shrink_capacity() {
+    fn capacity(sem: &Sem) -> u32 {
+        let mut acquired = 0;
+        while sem.try_wait() {
+            acquired += 1;
+        }
+        sem.signal_many(acquired);
+        acquired
+    }
+
+    let sem = Sem::new(2).unwrap();
+    sem.grow(3);
+    assert_eq!(capacity(&sem), 5);
+
+    sem.shrink(2);
+    assert_eq!(capacity(&sem), 3);
+
+    assert_eq!(sem.try_shrink(1), 1);
+    assert_eq!(capacity(&sem), 2);
+
+    //Held permits cannot be removed without blocking
+    let first = sem.acquire();
+    assert_eq!(sem.try_shrink(2), 1);
+    assert_eq!(capacity(&sem), 0);
+
+    std::thread::scope(|scope| {
+        let shrink = scope.spawn(|| sem.shrink(1));
+        std::thread::sleep(time::Duration::from_millis(10));
+        assert!(!shrink.is_finished());
+        drop(first);
+    });
+    assert_eq!(capacity(&sem), 0);
+
+    sem.grow(1);
+    assert_eq!(capacity(&sem), 1);
+}
+
+#[cfg(all(feature = "lean_assert", debug_assertions, not(any(windows, target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"))))]
+#[test]
+fn should_report_failed_check_via_hook() {
+    use std::sync::Mutex;
+
+    //Other tests may fail checks concurrently, so every report is kept
+    static FAILED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    fn hook(msg: &'static str) {
+        FAILED.lock().unwrap().push(msg);
+    }
+
+    semka::assert::set_hook(hook);
+    let result = std::panic::catch_unwind(|| {
+        let sem = unsafe {
+            Sem::new_uninit()
+        };
+        sem.try_wait()
+    });
+    assert!(semka::assert::take_hook().is_some());
+    assert!(semka::assert::take_hook().is_none());
+
+    assert!(result.is_err());
+    assert!(FAILED.lock().unwrap().contains(&"try_wait() on uninitialized Sem"));
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn should_measure_time_blocked() {
+    const BLOCK: time::Duration = time::Duration::from_millis(50);
+
+    let sem = Sem::new(1).unwrap();
+    assert_eq!(sem.wait_timed(), time::Duration::from_secs(0));
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(BLOCK);
+            sem.signal();
+        });
+
+        let blocked = sem.wait_timed();
+        assert!(blocked >= BLOCK / 2);
+        assert!(blocked < BLOCK * 20);
+    });
+}
+
+#[test]
+fn should_not_close_manual_close_on_drop() {
+    use semka::ManualClose;
+    use std::{mem, ptr};
+
+    let mut storage = mem::MaybeUninit::new(ManualClose::new(Sem::new(0).unwrap()));
+    unsafe {
+        ptr::drop_in_place(storage.as_mut_ptr());
+    }
+
+    let sem: &Sem = unsafe {
+        &*storage.as_ptr()
+    };
+    assert!(sem.is_init());
+    sem.signal();
+    assert!(sem.try_wait());
+
+    unsafe {
+        sem.close();
+    }
+    assert!(!sem.is_init());
+
+    let sem = ManualClose::new(Sem::new(1).unwrap()).into_inner();
+    assert!(sem.try_wait());
+}
+
+#[test]
+fn should_not_block_on_zero_timeout() {
+    use semka::TimeoutPolicy;
+
+    const ITERATIONS: u32 = 1000;
+    let zero = time::Duration::from_secs(0);
+
+    let sem = Sem::new(1).unwrap();
+    assert!(sem.wait_timeout(zero));
+    assert!(!sem.wait_timeout(zero));
+    assert_eq!(sem.wait_timeout_with_policy(zero, TimeoutPolicy::Error), Ok(false));
+    sem.signal();
+    assert_eq!(sem.wait_timeout_with_policy(zero, TimeoutPolicy::Error), Ok(true));
+
+    let before = time::Instant::now();
+    for _ in 0..ITERATIONS {
+        assert!(!sem.wait_timeout(zero));
+    }
+    //Non-blocking path takes microseconds at most, while blocking would take at least scheduler tick per call
+    assert!(before.elapsed() < time::Duration::from_millis(u64::from(ITERATIONS)));
+}